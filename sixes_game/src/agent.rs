@@ -0,0 +1,46 @@
+use crate::book::{Book, BOOK_FILE};
+use crate::config::config_path;
+use crate::engine::Engine;
+use crate::rng::Rng;
+use crate::{Coord, Play, Sixes};
+
+// A computer player: plays from the opening book while the position is in it
+// and falls back to the engine search afterwards
+#[derive(Debug, Clone)]
+pub struct Agent {
+    engine: Engine,
+    book: Option<Book>,
+    rng: Rng,
+}
+
+impl Agent {
+    pub fn new(engine: Engine, book: Option<Book>, seed: u64) -> Self {
+        Agent {
+            engine,
+            book,
+            rng: Rng::new(seed),
+        }
+    }
+
+    // Loads the tuned weights and the opening book from next to the executable if present
+    pub fn from_config(depth: u8, seed: u64) -> Self {
+        Agent::new(
+            Engine::from_config(depth),
+            Book::load(config_path(BOOK_FILE)).ok(),
+            seed,
+        )
+    }
+
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    pub fn choose_move(&mut self, game: &Sixes) -> Option<(Coord, Play)> {
+        if let Some(book) = &self.book {
+            if let Some(book_move) = book.choose(game, &mut self.rng) {
+                return Some(book_move);
+            }
+        }
+        self.engine.best_move(game)
+    }
+}
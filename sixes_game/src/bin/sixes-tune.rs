@@ -0,0 +1,143 @@
+// Self-play data generation, evaluation tuning and opening book building
+//
+//   sixes-tune selfplay <games> <dataset> [depth] [seed]
+//   sixes-tune fit <dataset> [weights] [epochs] [learning rate]
+//   sixes-tune book <dataset> [book] [plies]
+//   sixes-tune puzzles <dataset> [puzzles] [max win]
+//
+// Weights, books and puzzles are written next to the executable unless a path
+// is given, which is where the game looks for the weights and the book.
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process;
+
+use sixes_game::agent::Agent;
+use sixes_game::book::{Book, BOOK_FILE};
+use sixes_game::config::config_path;
+use sixes_game::engine::DEFAULT_DEPTH;
+use sixes_game::eval::{Weights, WEIGHTS_FILE};
+use sixes_game::puzzle::{mine, PUZZLES_FILE};
+use sixes_game::rng::Rng;
+use sixes_game::tune::{dataset_line, error, fit, parse_dataset, parse_game_line, self_play};
+
+const MAX_PLIES: usize = 200;
+const RANDOMNESS: f32 = 0.1;
+const BOOK_PLIES: usize = 8;
+// Longest forced win searched for; each extra turn multiplies the search
+const MAX_WIN: u8 = 2;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("selfplay") if args.len() >= 3 => selfplay(&args[1..]),
+        Some("fit") if args.len() >= 2 => tune(&args[1..]),
+        Some("book") if args.len() >= 2 => book(&args[1..]),
+        Some("puzzles") if args.len() >= 2 => puzzles(&args[1..]),
+        _ => Err(String::from(
+            "usage: sixes-tune selfplay <games> <dataset> [depth] [seed]\n       sixes-tune fit <dataset> [weights] [epochs] [learning rate]\n       sixes-tune book <dataset> [book] [plies]\n       sixes-tune puzzles <dataset> [puzzles] [max win]",
+        )),
+    };
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
+}
+
+fn arg<T: std::str::FromStr>(args: &[String], index: usize, default: T) -> Result<T, String> {
+    match args.get(index) {
+        Some(value) => value
+            .parse()
+            .map_err(|_| format!("invalid argument: {}", value)),
+        None => Ok(default),
+    }
+}
+
+fn selfplay(args: &[String]) -> Result<(), String> {
+    let games: usize = arg(args, 0, 0)?;
+    let depth = arg(args, 2, DEFAULT_DEPTH)?;
+    let seed = arg(args, 3, 1)?;
+    let mut agent = Agent::from_config(depth, seed);
+    let mut rng = Rng::new(seed);
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&args[1])
+        .map_err(|e| e.to_string())?;
+    for game in 0..games {
+        let (moves, winner) = self_play(&mut agent, &mut rng, MAX_PLIES, RANDOMNESS);
+        writeln!(file, "{}", dataset_line(&moves, winner)).map_err(|e| e.to_string())?;
+        println!("game {}/{}: {} plies", game + 1, games, moves.len());
+    }
+    Ok(())
+}
+
+fn tune(args: &[String]) -> Result<(), String> {
+    let text = fs::read_to_string(&args[0]).map_err(|e| e.to_string())?;
+    let samples = parse_dataset(&text).map_err(|e| e.to_string())?;
+    let out = args
+        .get(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| config_path(WEIGHTS_FILE));
+    let epochs = arg(args, 2, 2000)?;
+    let learning_rate = arg(args, 3, 0.5)?;
+
+    let initial = Weights::load_or_default();
+    println!(
+        "{} samples, loss {}",
+        samples.len(),
+        error(&samples, &initial)
+    );
+    let tuned = fit(&samples, initial, epochs, learning_rate);
+    println!("tuned loss {}", error(&samples, &tuned));
+    print!("{}", tuned.to_config());
+    tuned.save(&out).map_err(|e| e.to_string())?;
+    println!("written to {}", out.display());
+    Ok(())
+}
+
+fn book(args: &[String]) -> Result<(), String> {
+    let text = fs::read_to_string(&args[0]).map_err(|e| e.to_string())?;
+    let out = args
+        .get(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| config_path(BOOK_FILE));
+    let plies = arg(args, 2, BOOK_PLIES)?;
+
+    let book = Book::from_games(&text, plies).map_err(|e| e.to_string())?;
+    println!("{} positions", book.len());
+    book.save(&out).map_err(|e| e.to_string())?;
+    println!("written to {}", out.display());
+    Ok(())
+}
+
+fn puzzles(args: &[String]) -> Result<(), String> {
+    let text = fs::read_to_string(&args[0]).map_err(|e| e.to_string())?;
+    let out = args
+        .get(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| config_path(PUZZLES_FILE));
+    let max_win = arg(args, 2, MAX_WIN)?;
+
+    let mut lines = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (moves, _) =
+            parse_game_line(line).ok_or_else(|| format!("invalid game on line {}", index + 1))?;
+        for puzzle in mine(&moves, max_win) {
+            let line = puzzle.to_line();
+            if !lines.contains(&line) {
+                println!("{}", line);
+                lines.push(line);
+            }
+        }
+    }
+    println!("{} puzzles", lines.len());
+    fs::write(&out, lines.join("\n") + "\n").map_err(|e| e.to_string())?;
+    println!("written to {}", out.display());
+    Ok(())
+}
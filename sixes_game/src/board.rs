@@ -1,6 +1,10 @@
+use std::fmt;
+use std::str::FromStr;
+
 use crate::player::PlayerID;
 
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Coord {
     A1,
     B1,
@@ -15,6 +19,21 @@ pub enum Coord {
     E3,
 }
 
+// Every line of three on the board, shared by scoring and evaluation
+pub const TRIPLES: [(Coord, Coord, Coord); 11] = [
+    (Coord::A1, Coord::B1, Coord::C1),
+    (Coord::B1, Coord::C1, Coord::D1),
+    (Coord::B1, Coord::B2, Coord::B3),
+    (Coord::C1, Coord::C2, Coord::C3),
+    (Coord::D1, Coord::D2, Coord::D3),
+    (Coord::B2, Coord::C2, Coord::D2),
+    (Coord::B3, Coord::C3, Coord::D3),
+    (Coord::C3, Coord::D3, Coord::E3),
+    (Coord::A1, Coord::B2, Coord::C3),
+    (Coord::B1, Coord::C2, Coord::D3),
+    (Coord::C1, Coord::D2, Coord::E3),
+];
+
 #[derive(Debug, Clone)]
 pub struct Board {
     hexes: Vec<Hex>,
@@ -36,10 +55,51 @@ pub struct Triple {
     player: PlayerID,
 }
 
+impl Coord {
+    pub const ALL: [Coord; 11] = [
+        Coord::A1,
+        Coord::B1,
+        Coord::B2,
+        Coord::B3,
+        Coord::C1,
+        Coord::C2,
+        Coord::C3,
+        Coord::D1,
+        Coord::D2,
+        Coord::D3,
+        Coord::E3,
+    ];
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCoordError(pub String);
+
+impl FromStr for Coord {
+    type Err = ParseCoordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Coord::ALL
+            .iter()
+            .find(|coord| coord.to_string().eq_ignore_ascii_case(s.trim()))
+            .copied()
+            .ok_or_else(|| ParseCoordError(s.to_string()))
+    }
+}
+
 impl Triple {
     pub fn player(&self) -> PlayerID {
         self.player
     }
+
+    pub fn coords(&self) -> [Coord; 3] {
+        [self.a, self.b, self.c]
+    }
 }
 
 impl Hex {
@@ -157,30 +217,16 @@ impl Board {
     }
 
     pub fn score(&mut self) -> Vec<Triple> {
-        let triples = vec![
-            (Coord::A1, Coord::B1, Coord::C1),
-            (Coord::B1, Coord::C1, Coord::D1),
-            (Coord::B1, Coord::B2, Coord::B3),
-            (Coord::C1, Coord::C2, Coord::C3),
-            (Coord::D1, Coord::D2, Coord::D3),
-            (Coord::B2, Coord::C2, Coord::D2),
-            (Coord::B3, Coord::C3, Coord::D3),
-            (Coord::C3, Coord::D3, Coord::E3),
-            (Coord::A1, Coord::B2, Coord::C3),
-            (Coord::B1, Coord::C2, Coord::D3),
-            (Coord::C1, Coord::D2, Coord::E3),
-        ];
-
         let mut triples_acc = Vec::new();
 
-        for (coord1, coord2, coord3) in triples {
+        for (coord1, coord2, coord3) in TRIPLES {
             if self.same_player(coord1, coord2, coord3) {
                 if let Some(player) = self.hex(coord1).player {
                     triples_acc.push(Triple {
                         a: coord1,
                         b: coord2,
                         c: coord3,
-                        player: player,
+                        player,
                     });
                 }
             }
@@ -0,0 +1,257 @@
+// Opening book keyed by `Sixes::position_hash`. The text format is one entry
+// per line: the hash in hex, the move in notation and its weight, e.g.
+//
+//   8c3f0e5a1b2d4c67 C2 12
+//
+// Lines starting with `#` are comments.
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::notation::{move_to_string, parse_move};
+use crate::rng::Rng;
+use crate::tune::parse_game_line;
+use crate::{Coord, Play, Sixes};
+
+// Where `sixes-tune book` writes the book and agents look for it, see `config_path`
+pub const BOOK_FILE: &str = "sixes_book.txt";
+
+#[derive(Debug, Clone, Default)]
+pub struct Book {
+    entries: HashMap<u64, Vec<(Coord, Play, u32)>>,
+}
+
+#[derive(Debug)]
+pub enum BookError {
+    Io(io::Error),
+    InvalidLine(usize),
+}
+
+impl fmt::Display for BookError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BookError::Io(e) => write!(f, "{}", e),
+            BookError::InvalidLine(line) => write!(f, "invalid line {}", line),
+        }
+    }
+}
+
+impl Book {
+    pub fn new() -> Self {
+        Book::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn add(&mut self, hash: u64, coord: Coord, play: Play, weight: u32) {
+        let moves = self.entries.entry(hash).or_default();
+        match moves.iter_mut().find(|(c, p, _)| *c == coord && *p == play) {
+            Some(entry) => entry.2 += weight,
+            None => moves.push((coord, play, weight)),
+        }
+    }
+
+    pub fn moves(&self, game: &Sixes) -> Vec<(Coord, Play, u32)> {
+        self.entries
+            .get(&game.position_hash())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    // Weighted random choice among the legal book moves for this position
+    pub fn choose(&self, game: &Sixes, rng: &mut Rng) -> Option<(Coord, Play)> {
+        let legal = game.possible_moves();
+        let candidates: Vec<_> = self
+            .moves(game)
+            .into_iter()
+            .filter(|(coord, play, weight)| *weight > 0 && legal.contains(&(*coord, *play)))
+            .collect();
+        let total: u32 = candidates.iter().map(|(_, _, weight)| weight).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut pick = (rng.next_u64() % total as u64) as u32;
+        for (coord, play, weight) in candidates {
+            if pick < weight {
+                return Some((coord, play));
+            }
+            pick -= weight;
+        }
+        None
+    }
+
+    pub fn parse(text: &str) -> Result<Self, BookError> {
+        let mut book = Book::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || BookError::InvalidLine(index + 1);
+            let mut fields = line.split_whitespace();
+            let hash = fields
+                .next()
+                .and_then(|hash| u64::from_str_radix(hash, 16).ok())
+                .ok_or_else(invalid)?;
+            let (coord, play) = fields
+                .next()
+                .and_then(|mv| parse_move(mv).ok())
+                .ok_or_else(invalid)?;
+            let weight = fields
+                .next()
+                .and_then(|weight| weight.parse().ok())
+                .ok_or_else(invalid)?;
+            book.add(hash, coord, play, weight);
+        }
+        Ok(book)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut hashes: Vec<_> = self.entries.keys().collect();
+        hashes.sort();
+        let mut text = String::new();
+        for hash in hashes {
+            for (coord, play, weight) in &self.entries[hash] {
+                text.push_str(&format!(
+                    "{:016x} {} {}\n",
+                    hash,
+                    move_to_string(*coord, *play),
+                    weight
+                ));
+            }
+        }
+        text
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BookError> {
+        let text = fs::read_to_string(path).map_err(BookError::Io)?;
+        Book::parse(&text)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    // Builds a book from game records in the self-play dataset format
+    // ("<moves> <result>"). Over the first `plies` moves each move scores two
+    // points when the side that played it went on to win and one point when
+    // the game was unfinished; moves that only ever lost are left out.
+    pub fn from_games(text: &str, plies: usize) -> Result<Self, BookError> {
+        let mut book = Book::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (moves, winner) = parse_game_line(line).ok_or(BookError::InvalidLine(index + 1))?;
+
+            let mut game = Sixes::start();
+            for (coord, play) in moves.into_iter().take(plies) {
+                let weight = match winner {
+                    Some(player) if player == game.turn() => 2,
+                    Some(_) => 0,
+                    None => 1,
+                };
+                if weight > 0 {
+                    book.add(game.position_hash(), coord, play, weight);
+                }
+                if game.play(coord, play).is_err() {
+                    return Err(BookError::InvalidLine(index + 1));
+                }
+            }
+        }
+        Ok(book)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn book_lines_parse_and_round_trip() {
+        let text =
+            "# openings\n00000000000000ff C2 3\n\n00000000000000ff KB1 1\n000000000000abcd B2 2\n";
+        let book = Book::parse(text).unwrap();
+        assert_eq!(book.len(), 2);
+        assert_eq!(
+            book.entries[&0xff],
+            vec![(Coord::C2, Play::Stones, 3), (Coord::B1, Play::King, 1)]
+        );
+        let reparsed = Book::parse(&book.to_text()).unwrap();
+        assert_eq!(reparsed.to_text(), book.to_text());
+        assert_eq!(reparsed.entries, book.entries);
+
+        for (bad, line) in [
+            ("nothex C2 1", 1),
+            ("# ok\n00ff Z9 1", 2),
+            ("00ff C2", 1),
+            ("00ff C2 -1", 1),
+        ] {
+            assert!(
+                matches!(Book::parse(bad), Err(BookError::InvalidLine(n)) if n == line),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn from_games_weights_winning_moves() {
+        let book = Book::from_games("C2 B1 C1 1-0\nC2 B2 0-1\n# note\nC2 *\n", 2).unwrap();
+        let start = Sixes::start();
+        let mut after_c2 = Sixes::start();
+        after_c2.play(Coord::C2, Play::Stones).unwrap();
+
+        // Two points for the winner's C2, none for the loser's, one for the unfinished game
+        assert_eq!(book.moves(&start), vec![(Coord::C2, Play::Stones, 3)]);
+        // Only the first two plies are read and the losing B1 is left out
+        assert_eq!(book.moves(&after_c2), vec![(Coord::B2, Play::Stones, 2)]);
+        assert_eq!(book.len(), 2);
+
+        assert!(matches!(
+            Book::from_games("C2 C2 1-0", 2),
+            Err(BookError::InvalidLine(1))
+        ));
+        assert!(matches!(
+            Book::from_games("C2 B1\n", 2),
+            Err(BookError::InvalidLine(1))
+        ));
+    }
+
+    #[test]
+    fn choose_picks_legal_book_moves_by_seed() {
+        let start = Sixes::start();
+        let mut book = Book::new();
+        book.add(start.position_hash(), Coord::C2, Play::Stones, 3);
+        book.add(start.position_hash(), Coord::B1, Play::Stones, 1);
+        // Not legal at the start, so never picked
+        book.add(start.position_hash(), Coord::B2, Play::King, 100);
+
+        let picks = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..50)
+                .map(|_| book.choose(&start, &mut rng).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let first = picks(7);
+        assert!(first
+            .iter()
+            .all(|mv| *mv == (Coord::C2, Play::Stones) || *mv == (Coord::B1, Play::Stones)));
+        assert!(first.contains(&(Coord::C2, Play::Stones)));
+        assert_eq!(first, picks(7));
+
+        let mut off_book = Sixes::start();
+        off_book.play(Coord::A1, Play::Stones).unwrap();
+        assert_eq!(book.choose(&off_book, &mut Rng::new(7)), None);
+    }
+}
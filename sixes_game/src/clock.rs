@@ -0,0 +1,258 @@
+use std::time::Duration;
+
+use crate::PlayerID;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeControl {
+    // A fixed amount of time for the whole game
+    SuddenDeath {
+        base: Duration,
+    },
+    // `increment` is added to the mover's clock after each of their moves
+    Fischer {
+        base: Duration,
+        increment: Duration,
+    },
+    // Once `base` runs out the player gets `periods` periods of `period` each;
+    // a period is only used up when a move takes longer than it
+    Byoyomi {
+        base: Duration,
+        period: Duration,
+        periods: u32,
+    },
+}
+
+// Time left for both players. The clock does not read the system time itself,
+// callers pass in elapsed time so it works the same natively and on wasm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clock {
+    control: TimeControl,
+    remaining: [Duration; 2],
+    periods: [u32; 2],
+    byoyomi: [bool; 2],
+}
+
+fn index(player: PlayerID) -> usize {
+    match player {
+        PlayerID::One => 0,
+        PlayerID::Two => 1,
+    }
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Self {
+        let (base, periods) = match control {
+            TimeControl::SuddenDeath { base } => (base, 0),
+            TimeControl::Fischer { base, .. } => (base, 0),
+            TimeControl::Byoyomi { base, periods, .. } => (base, periods),
+        };
+        let mut clock = Clock {
+            control,
+            remaining: [base; 2],
+            periods: [periods; 2],
+            byoyomi: [false; 2],
+        };
+        // Without main time play starts straight in byoyomi
+        clock.tick(PlayerID::One, Duration::ZERO);
+        clock.tick(PlayerID::Two, Duration::ZERO);
+        clock
+    }
+
+    pub fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    // Time left on the player's main time or, in byoyomi, the current period
+    pub fn remaining(&self, player: PlayerID) -> Duration {
+        self.remaining[index(player)]
+    }
+
+    // Byoyomi periods left, including the one currently running
+    pub fn periods(&self, player: PlayerID) -> u32 {
+        self.periods[index(player)]
+    }
+
+    pub fn in_byoyomi(&self, player: PlayerID) -> bool {
+        self.byoyomi[index(player)]
+    }
+
+    // Restores a player's clock, e.g. from a saved game
+    pub fn set(&mut self, player: PlayerID, remaining: Duration, periods: u32, byoyomi: bool) {
+        let i = index(player);
+        self.remaining[i] = remaining;
+        self.periods[i] = periods;
+        self.byoyomi[i] = byoyomi;
+    }
+
+    pub fn flagged(&self, player: PlayerID) -> bool {
+        self.remaining(player).is_zero()
+    }
+
+    // Charges `elapsed` to the player whose clock is running
+    pub fn tick(&mut self, player: PlayerID, elapsed: Duration) {
+        let i = index(player);
+        let mut elapsed = elapsed;
+        while !self.flagged(player) {
+            if elapsed < self.remaining[i] {
+                self.remaining[i] -= elapsed;
+                return;
+            }
+            elapsed -= self.remaining[i];
+            self.remaining[i] = Duration::ZERO;
+            self.next_period(player);
+        }
+        // Main time that was zero from the start still has to hand over to byoyomi
+        if !self.byoyomi[i] {
+            self.next_period(player);
+        }
+    }
+
+    // Main time or the current period ran out
+    fn next_period(&mut self, player: PlayerID) {
+        let i = index(player);
+        if let TimeControl::Byoyomi { period, .. } = self.control {
+            if self.byoyomi[i] {
+                self.periods[i] = self.periods[i].saturating_sub(1);
+            }
+            self.byoyomi[i] = true;
+            if self.periods[i] > 0 {
+                self.remaining[i] = period;
+            }
+        }
+    }
+
+    // Called when `player` completes a move
+    pub fn press(&mut self, player: PlayerID) {
+        let i = index(player);
+        if self.flagged(player) {
+            return;
+        }
+        match self.control {
+            TimeControl::SuddenDeath { .. } => {}
+            TimeControl::Fischer { increment, .. } => self.remaining[i] += increment,
+            TimeControl::Byoyomi { period, .. } => {
+                if self.byoyomi[i] {
+                    self.remaining[i] = period;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameState, Play, Sixes, CENTER_HEX};
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn sudden_death_flags_at_exactly_zero() {
+        let mut clock = Clock::new(TimeControl::SuddenDeath { base: secs(10) });
+        clock.tick(PlayerID::One, secs(10) - Duration::from_millis(1));
+        assert!(!clock.flagged(PlayerID::One));
+        clock.tick(PlayerID::One, Duration::from_millis(1));
+        assert!(clock.flagged(PlayerID::One));
+        assert_eq!(clock.remaining(PlayerID::Two), secs(10));
+        // A flagged clock gains nothing from finishing the move
+        clock.press(PlayerID::One);
+        assert!(clock.flagged(PlayerID::One));
+    }
+
+    #[test]
+    fn fischer_adds_the_increment_on_press() {
+        let mut clock = Clock::new(TimeControl::Fischer {
+            base: secs(10),
+            increment: secs(2),
+        });
+        clock.tick(PlayerID::One, secs(3));
+        clock.press(PlayerID::One);
+        assert_eq!(clock.remaining(PlayerID::One), secs(9));
+        clock.press(PlayerID::Two);
+        assert_eq!(clock.remaining(PlayerID::Two), secs(12));
+    }
+
+    #[test]
+    fn byoyomi_uses_up_periods_and_resets_on_press() {
+        let mut clock = Clock::new(TimeControl::Byoyomi {
+            base: secs(5),
+            period: secs(3),
+            periods: 2,
+        });
+        clock.tick(PlayerID::One, secs(5));
+        assert!(clock.in_byoyomi(PlayerID::One));
+        assert_eq!(clock.remaining(PlayerID::One), secs(3));
+        assert_eq!(clock.periods(PlayerID::One), 2);
+
+        // A move inside the period keeps it and starts it afresh
+        clock.tick(PlayerID::One, secs(2));
+        clock.press(PlayerID::One);
+        assert_eq!(clock.remaining(PlayerID::One), secs(3));
+        assert_eq!(clock.periods(PlayerID::One), 2);
+
+        // Running past a period costs it and carries the overrun into the next
+        clock.tick(PlayerID::One, secs(4));
+        assert_eq!(clock.periods(PlayerID::One), 1);
+        assert_eq!(clock.remaining(PlayerID::One), secs(2));
+        assert!(!clock.flagged(PlayerID::One));
+
+        clock.tick(PlayerID::One, secs(2));
+        assert_eq!(clock.periods(PlayerID::One), 0);
+        assert!(clock.flagged(PlayerID::One));
+        assert!(!clock.in_byoyomi(PlayerID::Two));
+    }
+
+    #[test]
+    fn zero_main_time_starts_in_byoyomi() {
+        let clock = Clock::new(TimeControl::Byoyomi {
+            base: Duration::ZERO,
+            period: secs(3),
+            periods: 1,
+        });
+        for player in [PlayerID::One, PlayerID::Two] {
+            assert!(clock.in_byoyomi(player));
+            assert_eq!(clock.remaining(player), secs(3));
+            assert!(!clock.flagged(player));
+        }
+        let clock = Clock::new(TimeControl::SuddenDeath {
+            base: Duration::ZERO,
+        });
+        assert!(clock.flagged(PlayerID::One));
+    }
+
+    #[test]
+    fn set_restores_a_saved_clock() {
+        let control = TimeControl::Byoyomi {
+            base: secs(60),
+            period: secs(10),
+            periods: 3,
+        };
+        let mut clock = Clock::new(control);
+        clock.set(PlayerID::Two, secs(4), 1, true);
+        assert_eq!(clock.remaining(PlayerID::Two), secs(4));
+        assert_eq!(clock.periods(PlayerID::Two), 1);
+        assert!(clock.in_byoyomi(PlayerID::Two));
+        assert_eq!(clock.remaining(PlayerID::One), secs(60));
+    }
+
+    #[test]
+    fn running_out_of_time_loses_the_game() {
+        let mut game = Sixes::start_with_clock(TimeControl::SuddenDeath { base: secs(5) });
+        game.tick(secs(4));
+        assert_eq!(game.game_state(), GameState::Running);
+        game.tick(secs(1));
+        assert_eq!(game.game_state(), GameState::WinOnTime(PlayerID::Two));
+        assert!(game.play(CENTER_HEX, Play::Stones).is_err());
+        // Time no longer runs once the game is decided
+        game.lose_on_time(PlayerID::Two);
+        assert_eq!(game.game_state(), GameState::WinOnTime(PlayerID::Two));
+
+        let mut game = Sixes::start();
+        game.tick(secs(1000));
+        assert_eq!(game.game_state(), GameState::Running);
+        game.lose_on_time(PlayerID::Two);
+        assert_eq!(game.game_state(), GameState::WinOnTime(PlayerID::One));
+    }
+}
@@ -0,0 +1,17 @@
+// Where the files written by `sixes-tune` are looked for. They sit next to the
+// executable, so the tuned weights and the book are found whatever directory
+// the game is started from; `SIXES_CONFIG_DIR` points elsewhere instead.
+use std::env;
+use std::path::{Path, PathBuf};
+
+pub fn config_path(file: &str) -> PathBuf {
+    env::var_os("SIXES_CONFIG_DIR")
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        })
+        .unwrap_or_default()
+        .join(file)
+}
@@ -0,0 +1,164 @@
+use crate::eval::{evaluate, Weights, WIN_SCORE};
+use crate::{Coord, GameState, Play, Sixes};
+
+pub const DEFAULT_DEPTH: u8 = 4;
+
+// Root moves scored best first, and the line the search expects after the
+// best of them, starting with that move
+#[derive(Debug, Clone, Default)]
+pub struct Search {
+    pub scores: Vec<(Coord, Play, f32)>,
+    pub line: Vec<(Coord, Play)>,
+}
+
+// Depth-limited negamax with alpha-beta pruning over `Sixes::possible_moves`
+#[derive(Debug, Clone)]
+pub struct Engine {
+    weights: Weights,
+    depth: u8,
+}
+
+impl Engine {
+    pub fn new(weights: Weights, depth: u8) -> Self {
+        Engine { weights, depth }
+    }
+
+    // Uses the tuned weights from the config file when one exists
+    pub fn from_config(depth: u8) -> Self {
+        Engine::new(Weights::load_or_default(), depth)
+    }
+
+    pub fn weights(&self) -> Weights {
+        self.weights
+    }
+
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    // Scores every legal move for the side to move, best first
+    pub fn score_moves(&self, game: &Sixes) -> Vec<(Coord, Play, f32)> {
+        self.search(game, &|| false)
+            .map(|search| search.scores)
+            .unwrap_or_default()
+    }
+
+    pub fn best_move(&self, game: &Sixes) -> Option<(Coord, Play)> {
+        self.score_moves(game)
+            .first()
+            .map(|(coord, play, _)| (*coord, *play))
+    }
+
+    // Scores every legal move and keeps the line found under the best one.
+    // `cancelled` is checked at every node, so a search nobody is waiting for
+    // any more stops straight away and returns None.
+    pub fn search(&self, game: &Sixes, cancelled: &dyn Fn() -> bool) -> Option<Search> {
+        let mut scored = Vec::new();
+        for (coord, play) in game.possible_moves() {
+            let mut next = game.clone();
+            if next.play(coord, play).is_err() {
+                continue;
+            }
+            let mut line = vec![(coord, play)];
+            let score = -self.negamax(
+                &next,
+                self.depth.saturating_sub(1),
+                -f32::MAX,
+                f32::MAX,
+                &mut line,
+                cancelled,
+            )?;
+            scored.push((coord, play, score, line));
+        }
+        scored.sort_by(|a, b| b.2.total_cmp(&a.2));
+        let line = scored
+            .first()
+            .map(|(_, _, _, line)| line.clone())
+            .unwrap_or_default();
+        Some(Search {
+            scores: scored
+                .into_iter()
+                .map(|(coord, play, score, _)| (coord, play, score))
+                .collect(),
+            line,
+        })
+    }
+
+    // Appends the best continuation it finds to `line`
+    fn negamax(
+        &self,
+        game: &Sixes,
+        depth: u8,
+        mut alpha: f32,
+        beta: f32,
+        line: &mut Vec<(Coord, Play)>,
+        cancelled: &dyn Fn() -> bool,
+    ) -> Option<f32> {
+        if cancelled() {
+            return None;
+        }
+        let turn = game.turn();
+        if depth == 0 || game.game_state() != GameState::Running {
+            // Prefer quicker wins and slower losses
+            let score = evaluate(game, turn, &self.weights);
+            return Some(if score.abs() >= WIN_SCORE {
+                score * (1.0 + depth as f32 / 100.0)
+            } else {
+                score
+            });
+        }
+
+        let mut best = -f32::MAX;
+        let mut best_line = Vec::new();
+        let mut any_move = false;
+        for (coord, play) in game.possible_moves() {
+            let mut next = game.clone();
+            if next.play(coord, play).is_err() {
+                continue;
+            }
+            any_move = true;
+            let mut child_line = vec![(coord, play)];
+            let score =
+                -self.negamax(&next, depth - 1, -beta, -alpha, &mut child_line, cancelled)?;
+            if score > best {
+                best = score;
+                best_line = child_line;
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        line.extend(best_line);
+
+        if any_move {
+            Some(best)
+        } else {
+            Some(evaluate(game, turn, &self.weights))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_line_starts_with_the_best_move_and_is_legal() {
+        let engine = Engine::new(Weights::default(), 3);
+        let game = Sixes::from_moves(&[(Coord::C2, Play::Stones)]).unwrap();
+        let search = engine.search(&game, &|| false).unwrap();
+        assert_eq!(search.scores, engine.score_moves(&game));
+        assert_eq!(search.line.first(), engine.best_move(&game).as_ref());
+        assert!(!search.line.is_empty() && search.line.len() <= 3);
+        let mut history = game.history();
+        history.extend(&search.line);
+        assert!(Sixes::from_moves(&history).is_ok());
+    }
+
+    #[test]
+    fn cancelled_searches_stop() {
+        let engine = Engine::new(Weights::default(), 6);
+        assert!(engine.search(&Sixes::start(), &|| true).is_none());
+    }
+}
@@ -0,0 +1,182 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::board::TRIPLES;
+use crate::config::config_path;
+use crate::{PlayerID, Sixes, CENTER_HEX};
+
+// Where the tuner writes its output and the engine looks for it, see `config_path`
+pub const WEIGHTS_FILE: &str = "sixes_weights.cfg";
+pub const FEATURE_NAMES: [&str; FEATURE_COUNT] = ["centre", "open_triples", "stones", "king"];
+pub const FEATURE_COUNT: usize = 4;
+pub const WIN_SCORE: f32 = 10000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weights {
+    pub centre: f32,
+    pub open_triples: f32,
+    pub stones: f32,
+    pub king: f32,
+}
+
+#[derive(Debug)]
+pub enum WeightsError {
+    Io(io::Error),
+    Parse(String),
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Weights {
+            centre: 1.0,
+            open_triples: 0.5,
+            stones: 0.1,
+            king: 0.8,
+        }
+    }
+}
+
+impl Weights {
+    pub fn from_array(values: [f32; FEATURE_COUNT]) -> Self {
+        Weights {
+            centre: values[0],
+            open_triples: values[1],
+            stones: values[2],
+            king: values[3],
+        }
+    }
+
+    pub fn to_array(&self) -> [f32; FEATURE_COUNT] {
+        [self.centre, self.open_triples, self.stones, self.king]
+    }
+
+    // Parses `name = value` lines; `#` starts a comment and missing names keep their default
+    pub fn parse(text: &str) -> Result<Self, WeightsError> {
+        let mut values = Weights::default().to_array();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| WeightsError::Parse(line.to_string()))?;
+            let index = FEATURE_NAMES
+                .iter()
+                .position(|feature| *feature == name.trim())
+                .ok_or_else(|| WeightsError::Parse(line.to_string()))?;
+            values[index] = value
+                .trim()
+                .parse()
+                .map_err(|_| WeightsError::Parse(line.to_string()))?;
+        }
+        Ok(Weights::from_array(values))
+    }
+
+    pub fn to_config(&self) -> String {
+        FEATURE_NAMES
+            .iter()
+            .zip(self.to_array())
+            .map(|(name, value)| format!("{} = {}\n", name, value))
+            .collect()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WeightsError> {
+        let text = fs::read_to_string(path).map_err(WeightsError::Io)?;
+        Weights::parse(&text)
+    }
+
+    // Falls back to the built-in weights when no tuned config is present
+    pub fn load_or_default() -> Self {
+        Weights::load(config_path(WEIGHTS_FILE)).unwrap_or_default()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_config())
+    }
+}
+
+// Feature differences (player minus opponent) in the order of FEATURE_NAMES
+pub fn features(game: &Sixes, player: PlayerID) -> [f32; FEATURE_COUNT] {
+    let board = &game.board;
+    let owner = |coord| board.hex(coord).player();
+
+    let centre = match owner(CENTER_HEX) {
+        Some(id) if id == player => 1.0,
+        Some(_) => -1.0,
+        None => 0.0,
+    };
+
+    // A line is open for a player when they hold part of it and the opponent holds none
+    let mut open_triples = 0.0;
+    for (a, b, c) in TRIPLES {
+        let owners = [owner(a), owner(b), owner(c)];
+        let mine = owners.iter().filter(|o| **o == Some(player)).count();
+        let theirs = owners
+            .iter()
+            .filter(|o| **o == Some(player.other()))
+            .count();
+        if theirs == 0 {
+            open_triples += mine as f32;
+        } else if mine == 0 {
+            open_triples -= theirs as f32;
+        }
+    }
+
+    let me = game.player(player);
+    let them = game.player(player.other());
+    let stones = me.stones() as f32 - them.stones() as f32;
+    let king = me.king() as u8 as f32 - them.king() as u8 as f32;
+
+    [centre, open_triples, stones, king]
+}
+
+// Static evaluation from the point of view of `player`
+pub fn evaluate(game: &Sixes, player: PlayerID, weights: &Weights) -> f32 {
+    match game.game_state().winner() {
+        Some(winner) if winner == player => WIN_SCORE,
+        Some(_) => -WIN_SCORE,
+        None => features(game, player)
+            .iter()
+            .zip(weights.to_array())
+            .map(|(feature, weight)| feature * weight)
+            .sum(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weights_parse_with_comments_and_defaults() {
+        let weights =
+            Weights::parse("# tuned\ncentre = 2.5\n\nking = -1 # rarely worth it\n").unwrap();
+        assert_eq!(
+            weights,
+            Weights {
+                centre: 2.5,
+                king: -1.0,
+                ..Weights::default()
+            }
+        );
+        assert_eq!(Weights::parse(&weights.to_config()).unwrap(), weights);
+    }
+
+    #[test]
+    fn weights_reject_unknown_names_and_bad_values() {
+        assert!(matches!(
+            Weights::parse("height = 1"),
+            Err(WeightsError::Parse(_))
+        ));
+        assert!(matches!(
+            Weights::parse("stones = lots"),
+            Err(WeightsError::Parse(_))
+        ));
+        assert!(matches!(
+            Weights::parse("stones 1"),
+            Err(WeightsError::Parse(_))
+        ));
+    }
+}
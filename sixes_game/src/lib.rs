@@ -1,8 +1,22 @@
+pub mod agent;
 mod board;
+pub mod book;
+pub mod clock;
+pub mod config;
+pub mod engine;
+pub mod eval;
+pub mod notation;
 mod player;
+pub mod puzzle;
+pub mod record;
+pub mod rng;
+pub mod tune;
 
-pub use crate::board::Coord;
-use crate::board::{Board, Triple};
+use std::time::Duration;
+
+use crate::board::Board;
+pub use crate::board::{Coord, ParseCoordError, Triple};
+use crate::clock::{Clock, TimeControl};
 use crate::player::Player;
 pub use crate::player::PlayerID;
 pub const CENTER_HEX: Coord = Coord::C2;
@@ -16,14 +30,31 @@ pub struct Sixes {
     last_captured: Option<Coord>,
     last_scored: Vec<Triple>,
     game_state: GameState,
+    history: Vec<(Coord, Play)>,
+    clock: Option<Clock>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Play {
     Stones,
     King,
 }
 
+// What a move would do, worked out without touching the game
+#[derive(Debug, Clone)]
+pub struct Preview {
+    pub after: Sixes,
+    // Stones on the hex after the move, none for a king play
+    pub stack: u8,
+    // Opposing stones taken off the hex
+    pub removed: u8,
+    // Stones returned to the mover's stock for playing on an empty hex
+    pub bonus: u8,
+    // Lines of three the move forms
+    pub triples: Vec<Triple>,
+}
+
 #[derive(Debug, Clone)]
 pub enum GameError {
     InvalidPlay,
@@ -31,9 +62,20 @@ pub enum GameError {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameState {
     Running,
     Win(PlayerID),
+    WinOnTime(PlayerID), // The other player ran out of time
+}
+
+impl GameState {
+    pub fn winner(&self) -> Option<PlayerID> {
+        match self {
+            GameState::Running => None,
+            GameState::Win(player) | GameState::WinOnTime(player) => Some(*player),
+        }
+    }
 }
 
 impl Sixes {
@@ -46,7 +88,39 @@ impl Sixes {
             last_captured: None,
             last_scored: Vec::new(),
             game_state: GameState::Running,
+            history: Vec::new(),
+            clock: None,
+        }
+    }
+
+    pub fn start_with_clock(control: TimeControl) -> Self {
+        let mut game = Sixes::start();
+        game.clock = Some(Clock::new(control));
+        game
+    }
+
+    // Replays a move list from the starting position
+    pub fn from_moves(moves: &[(Coord, Play)]) -> Result<Self, GameError> {
+        let mut game = Sixes::start();
+        for (coord, play) in moves {
+            game.play(*coord, *play)?;
         }
+        Ok(game)
+    }
+
+    // Takes back the last move by replaying the rest of the history. The clock
+    // is left as it is, time spent on the move is not refunded. A finished game
+    // stays finished, so nothing is taken back once it is decided.
+    pub fn undo(&mut self) -> Option<(Coord, Play)> {
+        if self.game_state != GameState::Running {
+            return None;
+        }
+        let (last, earlier) = self.history.split_last()?;
+        let last = *last;
+        let mut game = Sixes::from_moves(earlier).expect("history replays cleanly");
+        game.clock = self.clock.take();
+        *self = game;
+        Some(last)
     }
 
     pub fn reset(&mut self) {
@@ -57,13 +131,15 @@ impl Sixes {
         self.last_captured = None;
         self.last_scored = Vec::new();
         self.game_state = GameState::Running;
+        self.history = Vec::new();
+        self.clock = self.clock.as_ref().map(|clock| Clock::new(clock.control()));
     }
 
     pub fn play(&mut self, coord: Coord, play: Play) -> Result<(), GameError> {
         if self.game_state != GameState::Running {
             return Err(GameError::InvalidPlay);
         }
-        if self.check_playable(coord, play.clone()) == false {
+        if !self.check_playable(coord, play) {
             return Err(GameError::InvalidPlay);
         }
         let hex = self.board.hex(coord);
@@ -94,14 +170,11 @@ impl Sixes {
                 self.board.play_stone(coord, self.turn);
             }
             Play::King => {
-                match hex.player() {
-                    Some(player_id) => {
-                        match player_id {
-                            PlayerID::One => self.player_one.add_to_graveyard(hex.stone()),
-                            PlayerID::Two => self.player_two.add_to_graveyard(hex.stone()),
-                        };
-                    }
-                    None => {}
+                if let Some(player_id) = hex.player() {
+                    match player_id {
+                        PlayerID::One => self.player_one.add_to_graveyard(hex.stone()),
+                        PlayerID::Two => self.player_two.add_to_graveyard(hex.stone()),
+                    };
                 }
 
                 match self.turn {
@@ -113,6 +186,10 @@ impl Sixes {
         }
 
         self.last_captured = Some(coord);
+        self.history.push((coord, play));
+        if let Some(clock) = &mut self.clock {
+            clock.press(self.turn);
+        }
         self.score()?;
         self.next_turn();
         Result::Ok(())
@@ -126,22 +203,14 @@ impl Sixes {
 
         let hex = self.board.hex(coord);
 
-        match self.last_captured {
-            Some(last_caputred) => {
-                if last_caputred == coord {
-                    return false;
-                }
-            }
-            None => {}
+        if self.last_captured == Some(coord) {
+            return false;
         }
 
         match play {
             Play::Stones => {
                 if let Some(player_id) = hex.player() {
-                    if player_id != player.id()
-                        && player.stones() > hex.stone()
-                        && hex.king() == false
-                    {
+                    if player_id != player.id() && player.stones() > hex.stone() && !hex.king() {
                         return true;
                     }
                     return false;
@@ -153,7 +222,7 @@ impl Sixes {
                     if player_id != player.id()
                         && player.king()
                         && hex.stone() > 0
-                        && hex.king() == false
+                        && !hex.king()
                         && hex.coord() != CENTER_HEX
                     {
                         return true;
@@ -178,9 +247,9 @@ impl Sixes {
         // if all of stay_scored is the same player return ok
         if stay_scored
             .iter()
-            .all(|item| item.eq(stay_scored.first().unwrap_or(&item)))
+            .all(|item| item.eq(stay_scored.first().unwrap_or(item)))
         {
-            if stay_scored.len() > 0 {
+            if !stay_scored.is_empty() {
                 self.game_state = GameState::Win(stay_scored[0]);
             }
             Result::Ok(())
@@ -189,10 +258,38 @@ impl Sixes {
         }
     }
 
+    // Runs the clock of the side to move; flagging ends the game
+    pub fn tick(&mut self, elapsed: Duration) {
+        if self.game_state != GameState::Running {
+            return;
+        }
+        if let Some(clock) = &mut self.clock {
+            clock.tick(self.turn, elapsed);
+            if clock.flagged(self.turn) {
+                self.lose_on_time(self.turn);
+            }
+        }
+    }
+
+    pub fn lose_on_time(&mut self, player: PlayerID) {
+        if self.game_state == GameState::Running {
+            self.game_state = GameState::WinOnTime(player.other());
+        }
+    }
+
+    // Replaces the clock, e.g. when restoring a saved game
+    pub fn set_clock(&mut self, clock: Option<Clock>) {
+        self.clock = clock;
+    }
+
     fn next_turn(&mut self) {
-        match self.turn {
-            PlayerID::One => self.turn = PlayerID::Two,
-            PlayerID::Two => self.turn = PlayerID::One,
+        self.turn = self.turn.other();
+    }
+
+    fn player(&self, id: PlayerID) -> &Player {
+        match id {
+            PlayerID::One => &self.player_one,
+            PlayerID::Two => &self.player_two,
         }
     }
 }
@@ -203,7 +300,7 @@ impl Sixes {
         self.board
             .hexes()
             .iter()
-            .map(|hex| (hex.coord().clone(), hex.stone(), hex.king(), hex.player()))
+            .map(|hex| (hex.coord(), hex.stone(), hex.king(), hex.player()))
             .collect()
     }
 
@@ -240,6 +337,301 @@ impl Sixes {
     }
 
     pub fn turn(&self) -> PlayerID {
-        self.turn.clone()
+        self.turn
+    }
+
+    pub fn clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
+    }
+
+    pub fn last_captured(&self) -> Option<Coord> {
+        self.last_captured
+    }
+
+    pub fn history(&self) -> Vec<(Coord, Play)> {
+        self.history.clone()
+    }
+
+    // Lines of three on the board after the last move. Any of these still
+    // standing after the next move wins for its owner.
+    pub fn triples(&self) -> Vec<Triple> {
+        self.last_scored.clone()
+    }
+
+    // Plays the move on a copy of the game and reports the outcome
+    pub fn dry_run(&self, coord: Coord, play: Play) -> Result<Preview, GameError> {
+        let mut after = self.clone();
+        after.play(coord, play)?;
+        let before = self.board.hex(coord);
+        let removed = match before.player() {
+            Some(owner) if owner != self.turn => before.stone(),
+            _ => 0,
+        };
+        let bonus = match (play, before.player()) {
+            (Play::Stones, None) if coord != CENTER_HEX => 2,
+            _ => 0,
+        };
+        let stack = match play {
+            Play::Stones => after.board.hex(coord).stone(),
+            Play::King => 0,
+        };
+        let triples = after
+            .last_scored
+            .iter()
+            .filter(|triple| triple.player() == self.turn && !self.last_scored.contains(triple))
+            .copied()
+            .collect();
+        Ok(Preview {
+            after,
+            stack,
+            removed,
+            bonus,
+            triples,
+        })
+    }
+
+    // Moves for the side to move that complete a new line of three, with the
+    // line each one makes
+    pub fn threats(&self) -> Vec<(Coord, Play, Triple)> {
+        let mut threats = Vec::new();
+        for (coord, play) in self.possible_moves() {
+            let Ok(preview) = self.dry_run(coord, play) else {
+                continue;
+            };
+            for triple in preview.triples {
+                threats.push((coord, play, triple));
+            }
+        }
+        threats
+    }
+
+    // Stable FNV-1a hash of everything that affects future play, used as the
+    // opening book key. The move history is deliberately left out so that
+    // transpositions share an entry.
+    pub fn position_hash(&self) -> u64 {
+        let mut bytes = Vec::new();
+        let player_byte = |player: Option<PlayerID>| match player {
+            None => 0,
+            Some(PlayerID::One) => 1,
+            Some(PlayerID::Two) => 2,
+        };
+        for hex in self.board.hexes() {
+            bytes.extend([hex.stone(), hex.king() as u8, player_byte(hex.player())]);
+        }
+        for player in [&self.player_one, &self.player_two] {
+            bytes.extend([player.stones(), player.king() as u8, player.graveyard()]);
+        }
+        bytes.push(player_byte(Some(self.turn)));
+        bytes.push(self.last_captured.map_or(u8::MAX, |coord| coord as u8));
+        for triple in &self.last_scored {
+            bytes.extend(triple.coords().iter().map(|coord| *coord as u8));
+            bytes.push(player_byte(Some(triple.player())));
+        }
+
+        bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(moves: &[(Coord, Play)]) -> Sixes {
+        Sixes::from_moves(moves).unwrap()
+    }
+
+    #[test]
+    fn undo_takes_back_a_move() {
+        let mut game = game(&[(Coord::C2, Play::Stones)]);
+        assert_eq!(game.undo(), Some((Coord::C2, Play::Stones)));
+        assert!(game.history().is_empty());
+        assert_eq!(game.turn(), PlayerID::One);
+        assert_eq!(game.last_captured(), None);
+        assert_eq!(game.players(), Sixes::start().players());
+        assert_eq!(game.position_hash(), Sixes::start().position_hash());
+        assert_eq!(game.undo(), None);
+    }
+
+    #[test]
+    fn undo_returns_captured_stones_and_bonuses() {
+        let moves = [
+            (Coord::C2, Play::Stones),
+            (Coord::B1, Play::Stones),
+            (Coord::C1, Play::Stones),
+            (Coord::C2, Play::Stones),
+        ];
+        let mut played = game(&moves);
+        // Player Two took Player One's stone on C2
+        assert_eq!(
+            played.board()[5],
+            (Coord::C2, 2, false, Some(PlayerID::Two))
+        );
+        for n in (0..moves.len()).rev() {
+            assert_eq!(played.undo(), Some(moves[n]));
+            let replayed = game(&moves[..n]);
+            assert_eq!(played.board(), replayed.board());
+            assert_eq!(played.players(), replayed.players());
+            assert_eq!(played.position_hash(), replayed.position_hash());
+        }
+        // The two stones Player Two got back for playing on empty B1 are gone again
+        assert_eq!(played.players()[1], (PlayerID::Two, 12, true));
+    }
+
+    #[test]
+    fn undo_leaves_a_loss_on_time_alone() {
+        let mut game = Sixes::start_with_clock(TimeControl::SuddenDeath {
+            base: Duration::from_secs(5),
+        });
+        game.play(Coord::C2, Play::Stones).unwrap();
+        game.tick(Duration::from_secs(5));
+        assert_eq!(game.game_state(), GameState::WinOnTime(PlayerID::One));
+        assert_eq!(game.undo(), None);
+        assert_eq!(game.game_state(), GameState::WinOnTime(PlayerID::One));
+        assert_eq!(game.history(), [(Coord::C2, Play::Stones)]);
+    }
+
+    fn stones(coords: &[Coord]) -> Vec<(Coord, Play)> {
+        coords.iter().map(|coord| (*coord, Play::Stones)).collect()
+    }
+
+    #[test]
+    fn threats_find_each_players_open_line() {
+        // One holds C1 and C2, Two holds D1 and D2
+        let mut game = game(&stones(&[Coord::C2, Coord::D1, Coord::C1, Coord::D2]));
+        let threats = game.threats();
+        assert_eq!(threats.len(), 1);
+        let (coord, play, triple) = threats[0];
+        assert_eq!((coord, play), (Coord::C3, Play::Stones));
+        assert_eq!(triple.coords(), [Coord::C1, Coord::C2, Coord::C3]);
+        assert_eq!(triple.player(), PlayerID::One);
+
+        game.play(Coord::A1, Play::Stones).unwrap();
+        let threats = game.threats();
+        assert_eq!(threats.len(), 1);
+        let (coord, play, triple) = threats[0];
+        assert_eq!((coord, play), (Coord::D3, Play::Stones));
+        assert_eq!(triple.coords(), [Coord::D1, Coord::D2, Coord::D3]);
+        assert_eq!(triple.player(), PlayerID::Two);
+        assert!(game.triples().is_empty());
+    }
+
+    #[test]
+    fn triples_report_a_completed_line() {
+        let game = game(&stones(&[
+            Coord::C2,
+            Coord::D1,
+            Coord::C1,
+            Coord::D2,
+            Coord::C3,
+        ]));
+        let triples = game.triples();
+        assert_eq!(triples.len(), 1);
+        assert_eq!(triples[0].coords(), [Coord::C1, Coord::C2, Coord::C3]);
+        assert_eq!(triples[0].player(), PlayerID::One);
+        // The line only wins if it is still standing after Two's reply
+        assert_eq!(game.game_state(), GameState::Running);
+    }
+
+    #[test]
+    fn a_king_blocks_a_line() {
+        let line = [Coord::C1, Coord::C2, Coord::C3];
+        let mut moves = stones(&[Coord::C3, Coord::B1, Coord::C2, Coord::A1]);
+        let open = game(&moves);
+        assert!(open
+            .threats()
+            .iter()
+            .any(|(coord, _, triple)| *coord == Coord::C1 && triple.coords() == line));
+
+        // Two's king on C3 can neither be captured nor covered
+        moves[3] = (Coord::C3, Play::King);
+        let blocked = game(&moves);
+        assert!(blocked
+            .threats()
+            .iter()
+            .all(|(_, _, triple)| triple.coords() != line));
+        assert!(!blocked
+            .possible_moves()
+            .contains(&(Coord::C3, Play::Stones)));
+    }
+
+    // Previews the move, checks the preview against really playing it on a
+    // copy and that the game itself was left alone
+    fn preview(game: &Sixes, coord: Coord, play: Play) -> Preview {
+        let (board, players, history) = (game.board(), game.players(), game.history());
+        let preview = game.dry_run(coord, play).unwrap();
+        let mut played = game.clone();
+        played.play(coord, play).unwrap();
+
+        assert_eq!(preview.after.board(), played.board());
+        assert_eq!(preview.after.players(), played.players());
+        assert_eq!(preview.after.history(), played.history());
+        assert_eq!(preview.after.position_hash(), played.position_hash());
+        let new_triples: Vec<Triple> = played
+            .triples()
+            .into_iter()
+            .filter(|triple| triple.player() == game.turn() && !game.triples().contains(triple))
+            .collect();
+        assert_eq!(preview.triples, new_triples);
+
+        assert_eq!(game.board(), board);
+        assert_eq!(game.players(), players);
+        assert_eq!(game.history(), history);
+        preview
+    }
+
+    // Stones in hand for the player to move before and after the move
+    fn stock(game: &Sixes, preview: &Preview) -> (u8, u8) {
+        let index = game.turn() as usize;
+        (game.players()[index].1, preview.after.players()[index].1)
+    }
+
+    #[test]
+    fn dry_run_matches_a_plain_placement() {
+        let game = Sixes::start();
+        let edge = preview(&game, Coord::B1, Play::Stones);
+        assert_eq!((edge.stack, edge.removed, edge.bonus), (1, 0, 2));
+        assert!(edge.triples.is_empty());
+        assert_eq!(stock(&game, &edge), (12, 13));
+
+        // The centre gives no bonus
+        let centre = preview(&game, CENTER_HEX, Play::Stones);
+        assert_eq!((centre.stack, centre.removed, centre.bonus), (1, 0, 0));
+        assert_eq!(stock(&game, &centre), (12, 11));
+    }
+
+    #[test]
+    fn dry_run_matches_a_capture() {
+        let game = game(&stones(&[Coord::C2, Coord::B1, Coord::C1]));
+        let preview = preview(&game, Coord::C2, Play::Stones);
+        assert_eq!((preview.stack, preview.removed, preview.bonus), (2, 1, 0));
+        assert_eq!(stock(&game, &preview), (13, 11));
+    }
+
+    #[test]
+    fn dry_run_matches_a_king_play() {
+        let game = game(&stones(&[Coord::C3, Coord::B1, Coord::C2]));
+        let preview = preview(&game, Coord::C3, Play::King);
+        assert_eq!((preview.stack, preview.removed, preview.bonus), (0, 1, 0));
+        assert_eq!(preview.after.players()[1], (PlayerID::Two, 13, false));
+        assert!(preview.after.board()[6].2);
+    }
+
+    #[test]
+    fn dry_run_matches_a_triple() {
+        let game = game(&stones(&[Coord::C2, Coord::D1, Coord::C1, Coord::D2]));
+        let preview = preview(&game, Coord::C3, Play::Stones);
+        assert_eq!((preview.stack, preview.removed, preview.bonus), (1, 0, 2));
+        assert_eq!(preview.triples.len(), 1);
+        assert_eq!(
+            preview.triples[0].coords(),
+            [Coord::C1, Coord::C2, Coord::C3]
+        );
+
+        // Illegal moves are refused without touching the game
+        let history = game.history();
+        assert!(game.dry_run(Coord::D2, Play::Stones).is_err());
+        assert_eq!(game.history(), history);
     }
 }
@@ -0,0 +1,37 @@
+// Move notation: a stone play is written as the hex ("C2"), a king play is
+// prefixed with K ("KB1"). Game records are moves separated by whitespace.
+use crate::{Coord, Play};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMoveError(pub String);
+
+pub fn move_to_string(coord: Coord, play: Play) -> String {
+    match play {
+        Play::Stones => coord.to_string(),
+        Play::King => format!("K{}", coord),
+    }
+}
+
+pub fn parse_move(s: &str) -> Result<(Coord, Play), ParseMoveError> {
+    let s = s.trim();
+    let (play, coord) = match s.strip_prefix(['K', 'k']) {
+        Some(rest) => (Play::King, rest),
+        None => (Play::Stones, s),
+    };
+    let coord = coord
+        .parse::<Coord>()
+        .map_err(|_| ParseMoveError(s.to_string()))?;
+    Ok((coord, play))
+}
+
+pub fn moves_to_string(moves: &[(Coord, Play)]) -> String {
+    moves
+        .iter()
+        .map(|(coord, play)| move_to_string(*coord, *play))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn parse_moves(s: &str) -> Result<Vec<(Coord, Play)>, ParseMoveError> {
+    s.split_whitespace().map(parse_move).collect()
+}
@@ -1,11 +1,21 @@
 pub const STARTING_STONES: u8 = 12;
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlayerID {
     One,
     Two,
 }
 
+impl PlayerID {
+    pub fn other(&self) -> PlayerID {
+        match self {
+            PlayerID::One => PlayerID::Two,
+            PlayerID::Two => PlayerID::One,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Player {
     id: PlayerID,
@@ -25,7 +35,7 @@ impl Player {
     }
 
     pub fn id(&self) -> PlayerID {
-        self.id.clone()
+        self.id
     }
 
     pub fn stones(&self) -> u8 {
@@ -36,7 +46,6 @@ impl Player {
         self.king
     }
 
-    #[allow(dead_code)]
     pub fn graveyard(&self) -> u8 {
         self.graveyard
     }
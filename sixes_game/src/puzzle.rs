@@ -0,0 +1,243 @@
+// Puzzles: a position given as the moves that reach it, and a goal for the
+// side to move. The text format is one puzzle per line, the goal and the
+// moves split by a bar:
+//
+//   win 2 | C2 A1 B1 C2 C1 B1
+//   survive 2 | B1 C1 D1 B1 A1 D3
+//   prevent | A1 D3 B1 E3
+//
+// Lines starting with `#` are comments. Solutions are checked by searching
+// every line of play to the end of the goal, not with the evaluation, so a
+// move the solver accepts really does meet the goal against any defence.
+use std::fmt;
+
+use crate::notation::{moves_to_string, parse_moves};
+use crate::{Coord, GameError, GameState, Play, PlayerID, Sixes};
+
+// Puzzles that ship with the game, mined from self-play with `sixes-tune puzzles`
+const BUILT_IN: &str = include_str!("puzzles.txt");
+// Where `sixes-tune puzzles` writes newly mined puzzles, see `config_path`
+pub const PUZZLES_FILE: &str = "sixes_puzzles.txt";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    // Win within this many of your own turns
+    WinIn(u8),
+    // Still not have lost after this many of your turns and the replies
+    Survive(u8),
+    // Leave the opponent without a triple after their reply
+    PreventTriple,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    InvalidLine(usize),
+    IllegalMoves(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub goal: Goal,
+    pub moves: Vec<(Coord, Play)>,
+}
+
+impl Goal {
+    // The goal left after a correct move and the opponent's reply
+    pub fn next(&self) -> Option<Goal> {
+        match *self {
+            Goal::WinIn(turns) if turns > 1 => Some(Goal::WinIn(turns - 1)),
+            Goal::Survive(turns) if turns > 1 => Some(Goal::Survive(turns - 1)),
+            _ => None,
+        }
+    }
+
+    fn to_key(self) -> String {
+        match self {
+            Goal::WinIn(turns) => format!("win {}", turns),
+            Goal::Survive(turns) => format!("survive {}", turns),
+            Goal::PreventTriple => String::from("prevent"),
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Goal> {
+        let mut fields = key.split_whitespace();
+        let goal = match (fields.next()?, fields.next()) {
+            ("win", Some(turns)) => Goal::WinIn(turns.parse().ok().filter(|t| *t > 0)?),
+            ("survive", Some(turns)) => Goal::Survive(turns.parse().ok().filter(|t| *t > 0)?),
+            ("prevent", None) => Goal::PreventTriple,
+            _ => return None,
+        };
+        fields.next().is_none().then_some(goal)
+    }
+}
+
+impl fmt::Display for Goal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Goal::WinIn(turns) => write!(f, "Win in {}", turns),
+            Goal::Survive(1) => write!(f, "Survive the next turn"),
+            Goal::Survive(turns) => write!(f, "Survive {} turns", turns),
+            Goal::PreventTriple => write!(f, "Stop the triple"),
+        }
+    }
+}
+
+impl Puzzle {
+    pub fn position(&self) -> Result<Sixes, GameError> {
+        Sixes::from_moves(&self.moves)
+    }
+
+    pub fn to_line(&self) -> String {
+        format!("{} | {}", self.goal.to_key(), moves_to_string(&self.moves))
+    }
+
+    pub fn solutions(&self) -> Vec<(Coord, Play)> {
+        self.position()
+            .map(|game| solutions(&game, self.goal))
+            .unwrap_or_default()
+    }
+}
+
+pub fn parse_puzzles(text: &str) -> Result<Vec<Puzzle>, PuzzleError> {
+    let mut puzzles = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (goal, moves) = line
+            .split_once('|')
+            .ok_or(PuzzleError::InvalidLine(index + 1))?;
+        let puzzle = Puzzle {
+            goal: Goal::from_key(goal).ok_or(PuzzleError::InvalidLine(index + 1))?,
+            moves: parse_moves(moves).map_err(|_| PuzzleError::InvalidLine(index + 1))?,
+        };
+        if puzzle.position().is_err() {
+            return Err(PuzzleError::IllegalMoves(index + 1));
+        }
+        puzzles.push(puzzle);
+    }
+    Ok(puzzles)
+}
+
+pub fn built_in() -> Vec<Puzzle> {
+    parse_puzzles(BUILT_IN).expect("built-in puzzles parse")
+}
+
+// The moves that meet `goal` for the side to move, against any defence
+pub fn solutions(game: &Sixes, goal: Goal) -> Vec<(Coord, Play)> {
+    if game.game_state() != GameState::Running {
+        return Vec::new();
+    }
+    let player = game.turn();
+    game.possible_moves()
+        .into_iter()
+        .filter(|(coord, play)| meets(game, *coord, *play, player, goal))
+        .collect()
+}
+
+fn solvable(game: &Sixes, goal: Goal) -> bool {
+    game.game_state() == GameState::Running
+        && game
+            .possible_moves()
+            .into_iter()
+            .any(|(coord, play)| meets(game, coord, play, game.turn(), goal))
+}
+
+// Whether `player` playing `play` on `coord` meets `goal` whatever the reply
+fn meets(game: &Sixes, coord: Coord, play: Play, player: PlayerID, goal: Goal) -> bool {
+    let mut after = game.clone();
+    if after.play(coord, play).is_err() {
+        return false;
+    }
+    if let Some(winner) = after.game_state().winner() {
+        return winner == player;
+    }
+    let replies = after.possible_moves();
+    // A game the opponent cannot go on with is not a win, but nor is it lost
+    if replies.is_empty() {
+        return !matches!(goal, Goal::WinIn(_));
+    }
+    replies.into_iter().all(|(coord, play)| {
+        let mut reply = after.clone();
+        if reply.play(coord, play).is_err() {
+            return true;
+        }
+        if let Some(winner) = reply.game_state().winner() {
+            return winner == player;
+        }
+        match (goal, goal.next()) {
+            (Goal::PreventTriple, _) => reply.triples().iter().all(|t| t.player() == player),
+            (_, Some(next)) => solvable(&reply, next),
+            (Goal::Survive(_), None) => true,
+            (Goal::WinIn(_), None) => false,
+        }
+    })
+}
+
+// Positions in a game that make good puzzles: the side to move has a quickest
+// forced win of at most `max_win` turns, or has to find one of few moves that
+// stop a triple or survive the next two turns. A puzzle is only kept when at
+// most a third of the legal moves solve it.
+pub fn mine(moves: &[(Coord, Play)], max_win: u8) -> Vec<Puzzle> {
+    let mut puzzles = Vec::new();
+    for ply in 0..moves.len() {
+        let Ok(game) = Sixes::from_moves(&moves[..ply]) else {
+            break;
+        };
+        if game.game_state() != GameState::Running {
+            break;
+        }
+        let legal = game.possible_moves().len();
+        let hard =
+            |solutions: &[(Coord, Play)]| !solutions.is_empty() && solutions.len() * 3 <= legal;
+
+        let quickest_win = (1..=max_win).find_map(|turns| {
+            let goal = Goal::WinIn(turns);
+            let found = solutions(&game, goal);
+            (!found.is_empty()).then_some((goal, found))
+        });
+        let goal = match quickest_win {
+            Some((goal, found)) if hard(&found) => Some(goal),
+            Some(_) => None,
+            None => [Goal::PreventTriple, Goal::Survive(2)]
+                .into_iter()
+                .find(|goal| hard(&solutions(&game, *goal))),
+        };
+        if let Some(goal) = goal {
+            puzzles.push(Puzzle {
+                goal,
+                moves: moves[..ply].to_vec(),
+            });
+        }
+    }
+    puzzles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_puzzles_have_solutions() {
+        for puzzle in built_in() {
+            assert!(!puzzle.solutions().is_empty(), "{}", puzzle.to_line());
+        }
+    }
+
+    #[test]
+    fn puzzle_lines_round_trip() {
+        let text = "win 2 | C2 B1 C1 C2 KB1\nprevent | C2 B1 C1\nsurvive 3 | C2";
+        let puzzles = parse_puzzles(text).unwrap();
+        let lines: Vec<String> = puzzles.iter().map(Puzzle::to_line).collect();
+        assert_eq!(lines.join("\n"), text);
+        assert_eq!(
+            parse_puzzles("win 0 | C2"),
+            Err(PuzzleError::InvalidLine(1))
+        );
+        assert_eq!(
+            parse_puzzles("prevent | C2 C2"),
+            Err(PuzzleError::IllegalMoves(1))
+        );
+    }
+}
@@ -0,0 +1,251 @@
+// Game records for saving and loading. A record is a few tag lines followed by
+// the moves in notation:
+//
+//   [TimeControl "fischer 600 5"]
+//   [ClockOne "581.250 0 0"]
+//   [ClockTwo "594.000 0 0"]
+//   [Result "*"]
+//   C2 B1 C1 KB1
+//
+// Clock tags hold the seconds left, the byoyomi periods left and whether the
+// player is in byoyomi (1) or not (0). The result is "1-0", "0-1" or "*", with
+// " time" appended for a loss on time, which replaying the moves cannot show.
+use std::time::Duration;
+
+use crate::clock::{Clock, TimeControl};
+use crate::notation::{moves_to_string, parse_moves};
+use crate::tune::result_to_string;
+use crate::{GameState, PlayerID, Sixes};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordError {
+    InvalidTag(String),
+    InvalidMoves(String),
+    IllegalMove,
+}
+
+fn time_control_to_string(control: TimeControl) -> String {
+    match control {
+        TimeControl::SuddenDeath { base } => format!("sudden {}", base.as_secs()),
+        TimeControl::Fischer { base, increment } => {
+            format!("fischer {} {}", base.as_secs(), increment.as_secs())
+        }
+        TimeControl::Byoyomi {
+            base,
+            period,
+            periods,
+        } => format!(
+            "byoyomi {} {} {}",
+            base.as_secs(),
+            period.as_secs(),
+            periods
+        ),
+    }
+}
+
+fn parse_time_control(value: &str) -> Option<TimeControl> {
+    let fields: Vec<&str> = value.split_whitespace().collect();
+    let seconds = |i: usize| fields.get(i)?.parse().ok().map(Duration::from_secs);
+    match *fields.first()? {
+        "sudden" => Some(TimeControl::SuddenDeath { base: seconds(1)? }),
+        "fischer" => Some(TimeControl::Fischer {
+            base: seconds(1)?,
+            increment: seconds(2)?,
+        }),
+        "byoyomi" => Some(TimeControl::Byoyomi {
+            base: seconds(1)?,
+            period: seconds(2)?,
+            periods: fields.get(3)?.parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+fn clock_to_string(clock: &Clock, player: PlayerID) -> String {
+    format!(
+        "{:.3} {} {}",
+        clock.remaining(player).as_secs_f64(),
+        clock.periods(player),
+        clock.in_byoyomi(player) as u8
+    )
+}
+
+fn parse_clock(clock: &mut Clock, player: PlayerID, value: &str) -> Option<()> {
+    let mut fields = value.split_whitespace();
+    let remaining = Duration::try_from_secs_f64(fields.next()?.parse().ok()?).ok()?;
+    let periods = fields.next()?.parse().ok()?;
+    let byoyomi = fields.next()? == "1";
+    clock.set(player, remaining, periods, byoyomi);
+    Some(())
+}
+
+pub fn to_record(game: &Sixes) -> String {
+    let mut record = String::new();
+    if let Some(clock) = game.clock() {
+        record.push_str(&format!(
+            "[TimeControl \"{}\"]\n",
+            time_control_to_string(clock.control())
+        ));
+        record.push_str(&format!(
+            "[ClockOne \"{}\"]\n",
+            clock_to_string(clock, PlayerID::One)
+        ));
+        record.push_str(&format!(
+            "[ClockTwo \"{}\"]\n",
+            clock_to_string(clock, PlayerID::Two)
+        ));
+    }
+    let result = match game.game_state() {
+        GameState::WinOnTime(winner) => format!("{} time", result_to_string(Some(winner))),
+        state => result_to_string(state.winner()).to_string(),
+    };
+    record.push_str(&format!("[Result \"{}\"]\n", result));
+    record.push_str(&moves_to_string(&game.history()));
+    record.push('\n');
+    record
+}
+
+pub fn from_record(text: &str) -> Result<Sixes, RecordError> {
+    let mut tags = Vec::new();
+    let mut moves = String::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(tag) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let (name, value) = tag
+                .split_once(' ')
+                .ok_or_else(|| RecordError::InvalidTag(line.to_string()))?;
+            let value = value.trim().trim_matches('"');
+            tags.push((name.to_string(), value.to_string()));
+        } else {
+            moves.push_str(line);
+            moves.push(' ');
+        }
+    }
+
+    let moves = parse_moves(&moves).map_err(|e| RecordError::InvalidMoves(e.0))?;
+    let mut game = Sixes::from_moves(&moves).map_err(|_| RecordError::IllegalMove)?;
+    let invalid = |name: &str, value: &str| RecordError::InvalidTag(format!("{} {}", name, value));
+
+    if let Some((_, value)) = tags.iter().find(|(name, _)| name == "TimeControl") {
+        let control = parse_time_control(value).ok_or_else(|| invalid("TimeControl", value))?;
+        let mut clock = Clock::new(control);
+        for (name, value) in &tags {
+            let player = match name.as_str() {
+                "ClockOne" => PlayerID::One,
+                "ClockTwo" => PlayerID::Two,
+                _ => continue,
+            };
+            parse_clock(&mut clock, player, value).ok_or_else(|| invalid(name, value))?;
+        }
+        game.set_clock(Some(clock));
+    }
+    if let Some((_, value)) = tags.iter().find(|(name, _)| name == "Result") {
+        match value.as_str() {
+            "1-0 time" => game.lose_on_time(PlayerID::Two),
+            "0-1 time" => game.lose_on_time(PlayerID::One),
+            _ => {}
+        }
+    }
+    Ok(game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coord, Play};
+
+    fn game(moves: &[(Coord, Play)], control: Option<TimeControl>) -> Sixes {
+        let mut game = match control {
+            Some(control) => Sixes::start_with_clock(control),
+            None => Sixes::start(),
+        };
+        for (coord, play) in moves {
+            game.tick(Duration::from_millis(1500));
+            game.play(*coord, *play).unwrap();
+        }
+        game
+    }
+
+    const MOVES: [(Coord, Play); 3] = [
+        (Coord::C2, Play::Stones),
+        (Coord::B1, Play::Stones),
+        (Coord::C1, Play::Stones),
+    ];
+
+    #[test]
+    fn plain_games_round_trip() {
+        let original = game(&MOVES, None);
+        let record = to_record(&original);
+        assert_eq!(record, "[Result \"*\"]\nC2 B1 C1\n");
+        let loaded = from_record(&record).unwrap();
+        assert_eq!(loaded.history(), original.history());
+        assert_eq!(loaded.game_state(), GameState::Running);
+        assert!(loaded.clock().is_none());
+        assert_eq!(to_record(&loaded), record);
+    }
+
+    #[test]
+    fn clocks_round_trip() {
+        let fischer = TimeControl::Fischer {
+            base: Duration::from_secs(600),
+            increment: Duration::from_secs(5),
+        };
+        let original = game(&MOVES, Some(fischer));
+        let record = to_record(&original);
+        assert!(record.starts_with(
+            "[TimeControl \"fischer 600 5\"]\n[ClockOne \"607.000 0 0\"]\n[ClockTwo \"603.500 0 0\"]\n"
+        ));
+        let loaded = from_record(&record).unwrap();
+        assert_eq!(loaded.clock(), original.clock());
+        assert_eq!(loaded.history(), original.history());
+
+        let byoyomi = TimeControl::Byoyomi {
+            base: Duration::ZERO,
+            period: Duration::from_secs(30),
+            periods: 3,
+        };
+        let mut original = game(&MOVES[..1], Some(byoyomi));
+        original.tick(Duration::from_secs(40));
+        let record = to_record(&original);
+        assert!(record.contains("[ClockTwo \"20.000 2 1\"]\n"));
+        assert_eq!(from_record(&record).unwrap().clock(), original.clock());
+    }
+
+    #[test]
+    fn losses_on_time_round_trip() {
+        let sudden = TimeControl::SuddenDeath {
+            base: Duration::from_secs(5),
+        };
+        let mut original = game(&MOVES[..1], Some(sudden));
+        original.tick(Duration::from_secs(5));
+        assert_eq!(original.game_state(), GameState::WinOnTime(PlayerID::One));
+        let record = to_record(&original);
+        assert!(record.contains("[Result \"1-0 time\"]\n"));
+        let loaded = from_record(&record).unwrap();
+        assert_eq!(loaded.game_state(), GameState::WinOnTime(PlayerID::One));
+        assert_eq!(loaded.clock(), original.clock());
+    }
+
+    #[test]
+    fn bad_records_are_rejected() {
+        assert_eq!(from_record("C2 C2").err(), Some(RecordError::IllegalMove));
+        assert_eq!(
+            from_record("C2 Z9").err(),
+            Some(RecordError::InvalidMoves(String::from("Z9")))
+        );
+        assert_eq!(
+            from_record("[Result]\nC2").err(),
+            Some(RecordError::InvalidTag(String::from("[Result]")))
+        );
+        assert_eq!(
+            from_record("[TimeControl \"fischer 600\"]\nC2").err(),
+            Some(RecordError::InvalidTag(String::from(
+                "TimeControl fischer 600"
+            )))
+        );
+        assert_eq!(
+            from_record("[TimeControl \"sudden 60\"]\n[ClockOne \"lots 0 0\"]\nC2").err(),
+            Some(RecordError::InvalidTag(String::from("ClockOne lots 0 0")))
+        );
+    }
+}
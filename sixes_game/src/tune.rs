@@ -0,0 +1,217 @@
+// Texel-style tuning of the evaluation weights. Self-play games are stored one
+// per line as the move record followed by the result ("1-0", "0-1" or "*"),
+// every position of a game becomes a sample labelled with that result, and the
+// weights are fitted by gradient descent on the logistic loss.
+use std::fmt;
+
+use crate::agent::Agent;
+use crate::eval::{features, Weights, FEATURE_COUNT};
+use crate::notation::{moves_to_string, parse_moves};
+use crate::rng::Rng;
+use crate::{Coord, GameState, Play, PlayerID, Sixes};
+
+// A finished or abandoned game: its moves and the winner, if any
+pub type GameRecord = (Vec<(Coord, Play)>, Option<PlayerID>);
+
+#[derive(Debug, Clone)]
+pub struct Sample {
+    features: [f32; FEATURE_COUNT],
+    result: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TuneError {
+    InvalidLine(usize),
+    InvalidGame(usize),
+}
+
+impl fmt::Display for TuneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TuneError::InvalidLine(line) => write!(f, "invalid game record on line {}", line),
+            TuneError::InvalidGame(line) => write!(f, "illegal move in game on line {}", line),
+        }
+    }
+}
+
+pub fn result_to_string(winner: Option<PlayerID>) -> &'static str {
+    match winner {
+        Some(PlayerID::One) => "1-0",
+        Some(PlayerID::Two) => "0-1",
+        None => "*",
+    }
+}
+
+// Splits a dataset line into its moves and winner (None for an unfinished game)
+pub fn parse_game_line(line: &str) -> Option<GameRecord> {
+    let (moves, result) = line.trim().rsplit_once(' ').unwrap_or(("", line.trim()));
+    let winner = match result {
+        "1-0" => Some(PlayerID::One),
+        "0-1" => Some(PlayerID::Two),
+        "*" => None,
+        _ => return None,
+    };
+    Some((parse_moves(moves).ok()?, winner))
+}
+
+pub fn dataset_line(moves: &[(Coord, Play)], winner: Option<PlayerID>) -> String {
+    format!("{} {}", moves_to_string(moves), result_to_string(winner))
+}
+
+// Plays one game against itself, picking a random legal move with probability
+// `randomness` so the dataset covers more than a single line of play
+pub fn self_play(
+    agent: &mut Agent,
+    rng: &mut Rng,
+    max_plies: usize,
+    randomness: f32,
+) -> GameRecord {
+    let mut game = Sixes::start();
+    for _ in 0..max_plies {
+        if let Some(player) = game.game_state().winner() {
+            return (game.history(), Some(player));
+        }
+        let moves = game.possible_moves();
+        if moves.is_empty() {
+            break;
+        }
+        let (coord, play) = if rng.next_f32() < randomness {
+            moves[rng.below(moves.len())]
+        } else {
+            match agent.choose_move(&game) {
+                Some(best) => best,
+                None => break,
+            }
+        };
+        if game.play(coord, play).is_err() {
+            break;
+        }
+    }
+    (game.history(), game.game_state().winner())
+}
+
+// Samples are labelled from player one's point of view: 1 win, 0 loss, 0.5 unfinished
+pub fn parse_dataset(text: &str) -> Result<Vec<Sample>, TuneError> {
+    let mut samples = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (moves, winner) = parse_game_line(line).ok_or(TuneError::InvalidLine(index + 1))?;
+        let result = match winner {
+            Some(PlayerID::One) => 1.0,
+            Some(PlayerID::Two) => 0.0,
+            None => 0.5,
+        };
+
+        let mut game = Sixes::start();
+        for (coord, play) in moves {
+            game.play(coord, play)
+                .map_err(|_| TuneError::InvalidGame(index + 1))?;
+            if game.game_state() == GameState::Running {
+                samples.push(Sample {
+                    features: features(&game, PlayerID::One),
+                    result,
+                });
+            }
+        }
+    }
+    Ok(samples)
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn predict(sample: &Sample, weights: &[f32; FEATURE_COUNT]) -> f32 {
+    sigmoid(
+        sample
+            .features
+            .iter()
+            .zip(weights)
+            .map(|(feature, weight)| feature * weight)
+            .sum(),
+    )
+}
+
+// Mean logistic loss (cross-entropy) between predicted win probability and
+// game result, the loss `fit` descends
+pub fn error(samples: &[Sample], weights: &Weights) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let weights = weights.to_array();
+    samples
+        .iter()
+        .map(|sample| {
+            // Clamped so a confident wrong prediction costs a lot rather than infinity
+            let p = predict(sample, &weights).clamp(1e-6, 1.0 - 1e-6);
+            -(sample.result * p.ln() + (1.0 - sample.result) * (1.0 - p).ln())
+        })
+        .sum::<f32>()
+        / samples.len() as f32
+}
+
+pub fn fit(samples: &[Sample], initial: Weights, epochs: usize, learning_rate: f32) -> Weights {
+    let mut weights = initial.to_array();
+    if samples.is_empty() {
+        return initial;
+    }
+    for _ in 0..epochs {
+        let mut gradient = [0.0; FEATURE_COUNT];
+        for sample in samples {
+            let delta = predict(sample, &weights) - sample.result;
+            for (g, feature) in gradient.iter_mut().zip(sample.features) {
+                *g += delta * feature;
+            }
+        }
+        for (weight, g) in weights.iter_mut().zip(gradient) {
+            *weight -= learning_rate * g / samples.len() as f32;
+        }
+    }
+    Weights::from_array(weights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dataset_lines_become_samples() {
+        let samples = parse_dataset("# comment\nC2 B1 C1 1-0\n\nB1 *\n").unwrap();
+        // One sample per position reached while the game was still running
+        assert_eq!(samples.len(), 4);
+        assert_eq!(samples[0].result, 1.0);
+        assert_eq!(samples[3].result, 0.5);
+        assert_eq!(
+            parse_dataset(
+                "C2 *
+C2 B1 2-2"
+            )
+            .err(),
+            Some(TuneError::InvalidLine(2))
+        );
+        assert_eq!(
+            parse_dataset("C2 C2 1-0").err(),
+            Some(TuneError::InvalidGame(1))
+        );
+    }
+
+    #[test]
+    fn fit_learns_which_feature_wins() {
+        let sample = |centre: f32, result: f32| Sample {
+            features: [centre, 0.0, 0.0, 0.0],
+            result,
+        };
+        let samples = [sample(1.0, 1.0), sample(-1.0, 0.0), sample(1.0, 1.0)];
+        let initial = Weights::from_array([0.0; FEATURE_COUNT]);
+        // An even prediction costs ln 2 on every sample
+        assert!((error(&samples, &initial) - 2f32.ln()).abs() < 1e-6);
+        let tuned = fit(&samples, initial, 200, 1.0);
+        assert!(tuned.centre > 1.0);
+        assert_eq!(tuned.king, 0.0);
+        assert!(error(&samples, &tuned) < error(&samples, &initial));
+        assert_eq!(fit(&[], initial, 10, 1.0), initial);
+    }
+}
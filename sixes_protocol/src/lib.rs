@@ -0,0 +1,209 @@
+// JSON-over-WebSocket protocol spoken by `sixes-server`, kept apart from
+// the server so the game client can use it without pulling in the server.
+//
+// Every message is a JSON object with a `type` field; the remaining fields
+// depend on the type. Players are "One" and "Two", moves use the notation
+// from `sixes_game::notation` ("C2" for stones, "KB1" for a king).
+//
+// Client to server:
+//   {"type": "create", "room": "lunch"}
+//   {"type": "join", "room": "lunch", "seat": "Two"}   seat may be null to take any free seat
+//   {"type": "join", "room": "lunch", "seat": "Two", "token": "9f0c..."}   reclaim a held seat
+//   {"type": "watch", "room": "lunch"}                 join read-only as a spectator
+//   {"type": "move", "notation": "KB1"}
+//   {"type": "resign"}
+//   {"type": "offer_draw"}
+//   {"type": "accept_draw"}
+//   {"type": "decline_draw"}
+//   {"type": "request_takeback"}                       undo your last move and any reply to it
+//   {"type": "accept_takeback"}
+//   {"type": "decline_takeback"}
+//   {"type": "chat", "text": "good game"}
+//
+// Server to client:
+//   {"type": "joined", "room": "lunch", "seat": "One", "token": "9f0c..."}
+//                                                       seat and token are null for spectators
+//   {"type": "state", "moves": ["C2", "B1"], "turn": "One", "last_captured": "B1",
+//    "game_state": "Running", "outcome": null, "seats": [true, false], "spectators": 2}
+//   {"type": "draw_offered", "by": "One"}
+//   {"type": "draw_declined", "by": "Two"}
+//   {"type": "takeback_requested", "by": "One"}
+//   {"type": "takeback_declined", "by": "Two"}
+//   {"type": "chat", "from": "One", "text": "good game"}
+//   {"type": "error", "code": "not_your_turn"}           see `ServerError` for the codes
+//
+// A `state` message is pushed to everyone in the room after every change and
+// always carries the complete move list, so a client that reconnects can
+// rebuild the game with `Sixes::from_moves` and stay in step with the
+// server's `last_captured` and scoring. Spectators receive the same pushes,
+// so one joining mid-game gets the whole history and can scrub back through it.
+// Spectators may chat; their messages have a null `from`.
+//
+// When a player's connection drops mid-game the server holds their seat, and
+// the room, for a couple of minutes. A `join` naming that seat and carrying
+// the token from the player's `joined` reply reclaims it, even before the
+// server has noticed the old connection go; without the token the seat counts
+// as taken, and a `join` with a null seat skips it. `seats` reports only
+// connected players. The server pings quiet connections and closes those that
+// stop answering.
+use serde::{Deserialize, Serialize};
+use sixes_game::notation::move_to_string;
+use sixes_game::{Coord, GameState, PlayerID, Sixes};
+
+pub const DEFAULT_ADDR: &str = "127.0.0.1:7878";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Create {
+        room: String,
+    },
+    Join {
+        room: String,
+        seat: Option<PlayerID>,
+        #[serde(default)]
+        token: Option<String>,
+    },
+    Watch {
+        room: String,
+    },
+    Move {
+        notation: String,
+    },
+    Resign,
+    OfferDraw,
+    AcceptDraw,
+    DeclineDraw,
+    RequestTakeback,
+    AcceptTakeback,
+    DeclineTakeback,
+    Chat {
+        text: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Joined {
+        room: String,
+        seat: Option<PlayerID>,
+        #[serde(default)]
+        token: Option<String>,
+    },
+    State(Snapshot),
+    DrawOffered {
+        by: PlayerID,
+    },
+    DrawDeclined {
+        by: PlayerID,
+    },
+    TakebackRequested {
+        by: PlayerID,
+    },
+    TakebackDeclined {
+        by: PlayerID,
+    },
+    Chat {
+        from: Option<PlayerID>,
+        text: String,
+    },
+    Error(ServerError),
+}
+
+// Why the server refused a request. Clients show these in their own language,
+// so they carry codes and the names involved rather than text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum ServerError {
+    Malformed,
+    RoomExists { room: String },
+    NoSuchRoom { room: String },
+    RoomFull { room: String },
+    SeatTaken { seat: PlayerID },
+    NotInRoom,
+    NotSeated,
+    InvalidMove { notation: String },
+    IllegalMove { notation: String },
+    NotYourTurn,
+    GameOver,
+    NoDrawOffer,
+    NothingToTakeBack,
+    NoTakebackRequest,
+}
+
+// How a game finished when the board alone does not say so
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Outcome {
+    Win { winner: PlayerID },
+    Resigned { winner: PlayerID },
+    Draw,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub moves: Vec<String>,
+    pub turn: PlayerID,
+    pub last_captured: Option<Coord>,
+    pub game_state: GameState,
+    pub outcome: Option<Outcome>,
+    pub seats: [bool; 2],
+    pub spectators: usize,
+}
+
+impl Snapshot {
+    pub fn new(
+        game: &Sixes,
+        outcome: Option<Outcome>,
+        seats: [bool; 2],
+        spectators: usize,
+    ) -> Self {
+        Snapshot {
+            moves: game
+                .history()
+                .iter()
+                .map(|(coord, play)| move_to_string(*coord, *play))
+                .collect(),
+            turn: game.turn(),
+            last_captured: game.last_captured(),
+            game_state: game.game_state(),
+            outcome,
+            seats,
+            spectators,
+        }
+    }
+}
+
+pub fn encode(message: &ServerMessage) -> String {
+    serde_json::to_string(message).expect("server messages always serialize")
+}
+
+pub fn encode_client(message: &ClientMessage) -> String {
+    serde_json::to_string(message).expect("client messages always serialize")
+}
+
+pub fn decode(text: &str) -> Result<ClientMessage, serde_json::Error> {
+    serde_json::from_str(text)
+}
+
+pub fn decode_server(text: &str) -> Result<ServerMessage, serde_json::Error> {
+    serde_json::from_str(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_are_flat_json_with_a_code() {
+        let error = ServerMessage::Error(ServerError::SeatTaken {
+            seat: PlayerID::Two,
+        });
+        let text = encode(&error);
+        assert_eq!(text, r#"{"type":"error","code":"seat_taken","seat":"Two"}"#);
+        assert_eq!(decode_server(&text).unwrap(), error);
+        let text = encode(&ServerMessage::Error(ServerError::NotYourTurn));
+        assert_eq!(text, r#"{"type":"error","code":"not_your_turn"}"#);
+    }
+}
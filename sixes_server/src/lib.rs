@@ -0,0 +1,3 @@
+pub mod lobby;
+
+pub use sixes_protocol as protocol;
@@ -0,0 +1,897 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+use sixes_game::notation::parse_move;
+use sixes_game::{GameState, PlayerID, Sixes};
+
+use crate::protocol::{ClientMessage, Outcome, ServerError, ServerMessage, Snapshot};
+
+pub type ClientId = u64;
+
+// How long a dropped player's seat, and an empty room with a game in
+// progress, is kept for them to reconnect
+pub const RECONNECT_GRACE: Duration = Duration::from_secs(120);
+
+#[derive(Debug)]
+struct Client {
+    sender: Sender<ServerMessage>,
+    room: Option<String>,
+    seat: Option<PlayerID>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Seat {
+    Free,
+    Taken(ClientId),
+    // Held for a player who dropped out of an unfinished game; only a join
+    // that names this seat and brings its token can take it before it expires
+    Reserved(Instant),
+}
+
+#[derive(Debug)]
+struct Room {
+    game: Sixes,
+    seats: [Seat; 2],
+    // Handed to whoever takes a free seat and needed to reclaim it once reserved
+    tokens: [String; 2],
+    members: Vec<ClientId>,
+    outcome: Option<Outcome>,
+    draw_offer: Option<PlayerID>,
+    takeback_request: Option<PlayerID>,
+}
+
+// All rooms and connected clients. Connection threads share one lobby behind
+// a mutex and forward every decoded message to `handle`; replies and pushes
+// go out through each client's channel.
+#[derive(Debug, Default)]
+pub struct Lobby {
+    clients: HashMap<ClientId, Client>,
+    rooms: HashMap<String, Room>,
+    next_id: ClientId,
+}
+
+fn seat_index(player: PlayerID) -> usize {
+    match player {
+        PlayerID::One => 0,
+        PlayerID::Two => 1,
+    }
+}
+
+// RandomState is keyed from the OS, so its hashes make tokens other clients
+// cannot guess
+fn new_token() -> String {
+    let state = RandomState::new();
+    format!("{:016x}{:016x}", state.hash_one(0u8), state.hash_one(1u8))
+}
+
+impl Room {
+    fn new() -> Self {
+        Room {
+            game: Sixes::start(),
+            seats: [Seat::Free, Seat::Free],
+            tokens: [String::new(), String::new()],
+            members: Vec::new(),
+            outcome: None,
+            draw_offer: None,
+            takeback_request: None,
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let taken = self.seats.map(|seat| matches!(seat, Seat::Taken(_)));
+        let seated = taken.iter().filter(|taken| **taken).count();
+        Snapshot::new(
+            &self.game,
+            self.outcome.clone(),
+            taken,
+            self.members.len() - seated,
+        )
+    }
+
+    fn finished(&self) -> bool {
+        self.outcome.is_some()
+    }
+
+    // Frees seats whose player did not come back in time
+    fn expire(&mut self, now: Instant) {
+        for seat in self.seats.iter_mut() {
+            if matches!(seat, Seat::Reserved(since) if now - *since >= RECONNECT_GRACE) {
+                *seat = Seat::Free;
+            }
+        }
+    }
+
+    // Nobody is in the room and nobody is expected back
+    fn abandoned(&self) -> bool {
+        self.members.is_empty()
+            && !self
+                .seats
+                .iter()
+                .any(|seat| matches!(seat, Seat::Reserved(_)))
+    }
+
+    // Plies to undo so that `player` is back to move before their last move;
+    // zero if they have not moved yet
+    fn takeback_plies(&self, player: PlayerID) -> usize {
+        let moves = self.game.history().len();
+        let plies = if self.game.turn() == player { 2 } else { 1 };
+        if plies > moves {
+            0
+        } else {
+            plies
+        }
+    }
+}
+
+impl Lobby {
+    pub fn new() -> Self {
+        Lobby::default()
+    }
+
+    pub fn connect(&mut self, sender: Sender<ServerMessage>) -> ClientId {
+        self.next_id += 1;
+        self.clients.insert(
+            self.next_id,
+            Client {
+                sender,
+                room: None,
+                seat: None,
+            },
+        );
+        self.next_id
+    }
+
+    // Reserves the client's seat if their game is still going, so the same
+    // player can take it again after reconnecting
+    pub fn disconnect(&mut self, id: ClientId) {
+        self.leave_room(id, true);
+        self.clients.remove(&id);
+    }
+
+    pub fn handle(&mut self, id: ClientId, message: ClientMessage) {
+        self.expire(Instant::now());
+        if let Err(error) = self.dispatch(id, message) {
+            self.send(id, ServerMessage::Error(error));
+        }
+    }
+
+    fn dispatch(&mut self, id: ClientId, message: ClientMessage) -> Result<(), ServerError> {
+        match message {
+            ClientMessage::Create { room } => {
+                if self.rooms.contains_key(&room) {
+                    return Err(ServerError::RoomExists { room });
+                }
+                self.rooms.insert(room.clone(), Room::new());
+                self.join(id, room, Some(PlayerID::One), None)
+            }
+            ClientMessage::Join { room, seat, token } => self.join(id, room, seat, token),
+            ClientMessage::Watch { room } => self.watch(id, room),
+            ClientMessage::Move { notation } => {
+                let (coord, play) =
+                    parse_move(&notation).map_err(|_| ServerError::InvalidMove {
+                        notation: notation.clone(),
+                    })?;
+                let (room, player) = self.seated(id)?;
+                if room.finished() {
+                    return Err(ServerError::GameOver);
+                }
+                if room.game.turn() != player {
+                    return Err(ServerError::NotYourTurn);
+                }
+                room.game
+                    .play(coord, play)
+                    .map_err(|_| ServerError::IllegalMove { notation })?;
+                room.draw_offer = None;
+                room.takeback_request = None;
+                if let GameState::Win(winner) = room.game.game_state() {
+                    room.outcome = Some(Outcome::Win { winner });
+                }
+                self.broadcast_state(id);
+                Ok(())
+            }
+            ClientMessage::Resign => {
+                let (room, player) = self.seated(id)?;
+                if room.finished() {
+                    return Err(ServerError::GameOver);
+                }
+                room.outcome = Some(Outcome::Resigned {
+                    winner: player.other(),
+                });
+                self.broadcast_state(id);
+                Ok(())
+            }
+            ClientMessage::OfferDraw => {
+                let (room, player) = self.seated(id)?;
+                if room.finished() {
+                    return Err(ServerError::GameOver);
+                }
+                room.draw_offer = Some(player);
+                self.broadcast(id, ServerMessage::DrawOffered { by: player });
+                Ok(())
+            }
+            ClientMessage::AcceptDraw => {
+                let (room, player) = self.seated(id)?;
+                if room.draw_offer != Some(player.other()) || room.finished() {
+                    return Err(ServerError::NoDrawOffer);
+                }
+                room.draw_offer = None;
+                room.outcome = Some(Outcome::Draw);
+                self.broadcast_state(id);
+                Ok(())
+            }
+            ClientMessage::DeclineDraw => {
+                let (room, player) = self.seated(id)?;
+                if room.draw_offer != Some(player.other()) {
+                    return Err(ServerError::NoDrawOffer);
+                }
+                room.draw_offer = None;
+                self.broadcast(id, ServerMessage::DrawDeclined { by: player });
+                Ok(())
+            }
+            ClientMessage::RequestTakeback => {
+                let (room, player) = self.seated(id)?;
+                if room.finished() {
+                    return Err(ServerError::GameOver);
+                }
+                if room.takeback_plies(player) == 0 {
+                    return Err(ServerError::NothingToTakeBack);
+                }
+                room.takeback_request = Some(player);
+                self.broadcast(id, ServerMessage::TakebackRequested { by: player });
+                Ok(())
+            }
+            ClientMessage::AcceptTakeback => {
+                let (room, player) = self.seated(id)?;
+                let by = player.other();
+                if room.takeback_request != Some(by) || room.finished() {
+                    return Err(ServerError::NoTakebackRequest);
+                }
+                room.takeback_request = None;
+                room.draw_offer = None;
+                for _ in 0..room.takeback_plies(by) {
+                    room.game.undo();
+                }
+                self.broadcast_state(id);
+                Ok(())
+            }
+            ClientMessage::DeclineTakeback => {
+                let (room, player) = self.seated(id)?;
+                if room.takeback_request != Some(player.other()) {
+                    return Err(ServerError::NoTakebackRequest);
+                }
+                room.takeback_request = None;
+                self.broadcast(id, ServerMessage::TakebackDeclined { by: player });
+                Ok(())
+            }
+            ClientMessage::Chat { text } => {
+                let from = self.client(id)?.seat;
+                if self.client(id)?.room.is_none() {
+                    return Err(ServerError::NotInRoom);
+                }
+                self.broadcast(id, ServerMessage::Chat { from, text });
+                Ok(())
+            }
+        }
+    }
+
+    fn join(
+        &mut self,
+        id: ClientId,
+        name: String,
+        seat: Option<PlayerID>,
+        token: Option<String>,
+    ) -> Result<(), ServerError> {
+        // Nothing changes until the seat is known to be available, so a
+        // refused join leaves the client where they were
+        let room = self
+            .rooms
+            .get(&name)
+            .ok_or_else(|| ServerError::NoSuchRoom { room: name.clone() })?;
+        // A client changing seats may keep or swap the one they already hold
+        let open = |player: PlayerID| {
+            let held = room.seats[seat_index(player)];
+            held == Seat::Free || held == Seat::Taken(id)
+        };
+        let seat = match seat {
+            Some(player) if open(player) => player,
+            // A taken seat can be reclaimed too, as a dropped connection is
+            // not always noticed before the player reconnects
+            Some(player) if token.as_ref() == Some(&room.tokens[seat_index(player)]) => player,
+            Some(player) => return Err(ServerError::SeatTaken { seat: player }),
+            None => [PlayerID::One, PlayerID::Two]
+                .into_iter()
+                .find(|player| open(*player))
+                .ok_or_else(|| ServerError::RoomFull { room: name.clone() })?,
+        };
+        let i = seat_index(seat);
+        let fresh = room.seats[i] == Seat::Free;
+        let stale = match room.seats[i] {
+            Seat::Taken(holder) if holder != id => Some(holder),
+            _ => None,
+        };
+        self.enter_room(id, &name);
+        // Dropping the old client closes its channel, which ends its connection
+        if let Some(holder) = stale {
+            self.clients.remove(&holder);
+        }
+
+        let room = self
+            .rooms
+            .get_mut(&name)
+            .ok_or_else(|| ServerError::NoSuchRoom { room: name.clone() })?;
+        if fresh {
+            room.tokens[i] = new_token();
+        }
+        room.members.retain(|member| Some(*member) != stale);
+        room.seats[i] = Seat::Taken(id);
+        room.members.push(id);
+        let token = room.tokens[i].clone();
+
+        let client = self.clients.get_mut(&id).ok_or(ServerError::NotInRoom)?;
+        client.room = Some(name.clone());
+        client.seat = Some(seat);
+        self.send(
+            id,
+            ServerMessage::Joined {
+                room: name,
+                seat: Some(seat),
+                token: Some(token),
+            },
+        );
+        self.broadcast_state(id);
+        Ok(())
+    }
+
+    fn watch(&mut self, id: ClientId, name: String) -> Result<(), ServerError> {
+        if !self.rooms.contains_key(&name) {
+            return Err(ServerError::NoSuchRoom { room: name });
+        }
+        self.enter_room(id, &name);
+
+        let room = self
+            .rooms
+            .get_mut(&name)
+            .ok_or_else(|| ServerError::NoSuchRoom { room: name.clone() })?;
+        room.members.push(id);
+        let client = self.clients.get_mut(&id).ok_or(ServerError::NotInRoom)?;
+        client.room = Some(name.clone());
+        self.send(
+            id,
+            ServerMessage::Joined {
+                room: name,
+                seat: None,
+                token: None,
+            },
+        );
+        self.broadcast_state(id);
+        Ok(())
+    }
+
+    // Takes the client out of whatever room they are in ahead of joining
+    // `name`. Within `name` itself only their seat is given up, so changing
+    // seats or sitting out to watch never closes the room under them.
+    fn enter_room(&mut self, id: ClientId, name: &str) {
+        let current = self.clients.get(&id).and_then(|client| client.room.clone());
+        if current.as_deref() != Some(name) {
+            self.leave_room(id, false);
+            return;
+        }
+        if let Some(room) = self.rooms.get_mut(name) {
+            room.members.retain(|member| *member != id);
+            for seat in room.seats.iter_mut() {
+                if *seat == Seat::Taken(id) {
+                    *seat = Seat::Free;
+                }
+            }
+        }
+        if let Some(client) = self.clients.get_mut(&id) {
+            client.seat = None;
+        }
+    }
+
+    // Drops reservations and empty rooms that have outlived the grace period
+    pub fn expire(&mut self, now: Instant) {
+        for room in self.rooms.values_mut() {
+            room.expire(now);
+        }
+        self.rooms.retain(|_, room| !room.abandoned());
+    }
+
+    // A seated player who drops out of an unfinished game keeps their seat
+    // reserved when `reserve` is set; moving to another room gives it up
+    fn leave_room(&mut self, id: ClientId, reserve: bool) {
+        let Some(name) = self.clients.get_mut(&id).and_then(|client| {
+            client.seat = None;
+            client.room.take()
+        }) else {
+            return;
+        };
+        if let Some(room) = self.rooms.get_mut(&name) {
+            room.members.retain(|member| *member != id);
+            let reserve = reserve && !room.finished();
+            for seat in room.seats.iter_mut() {
+                if *seat == Seat::Taken(id) {
+                    *seat = if reserve {
+                        Seat::Reserved(Instant::now())
+                    } else {
+                        Seat::Free
+                    };
+                }
+            }
+            if room.abandoned() {
+                self.rooms.remove(&name);
+            } else {
+                let snapshot = room.snapshot();
+                for member in room.members.clone() {
+                    self.send(member, ServerMessage::State(snapshot.clone()));
+                }
+            }
+        }
+    }
+
+    // A client the lobby does not know is in no room
+    fn client(&self, id: ClientId) -> Result<&Client, ServerError> {
+        self.clients.get(&id).ok_or(ServerError::NotInRoom)
+    }
+
+    // The room and seat of a client that is playing, not just watching
+    fn seated(&mut self, id: ClientId) -> Result<(&mut Room, PlayerID), ServerError> {
+        let client = self.client(id)?;
+        let seat = client.seat.ok_or(ServerError::NotSeated)?;
+        let name = client.room.clone().ok_or(ServerError::NotInRoom)?;
+        let room = self.rooms.get_mut(&name).ok_or(ServerError::NotInRoom)?;
+        Ok((room, seat))
+    }
+
+    fn send(&self, id: ClientId, message: ServerMessage) {
+        if let Some(client) = self.clients.get(&id) {
+            // A closed channel means the connection is going away; disconnect cleans up
+            let _ = client.sender.send(message);
+        }
+    }
+
+    // Sends to everyone in the same room as `id`
+    fn broadcast(&self, id: ClientId, message: ServerMessage) {
+        let Some(room) = self
+            .clients
+            .get(&id)
+            .and_then(|client| client.room.as_ref())
+            .and_then(|name| self.rooms.get(name))
+        else {
+            return;
+        };
+        for member in &room.members {
+            self.send(*member, message.clone());
+        }
+    }
+
+    fn broadcast_state(&self, id: ClientId) {
+        let snapshot = self
+            .clients
+            .get(&id)
+            .and_then(|client| client.room.as_ref())
+            .and_then(|name| self.rooms.get(name))
+            .map(Room::snapshot);
+        if let Some(snapshot) = snapshot {
+            self.broadcast(id, ServerMessage::State(snapshot));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::{self, Receiver};
+
+    use super::*;
+
+    fn connect(lobby: &mut Lobby) -> (ClientId, Receiver<ServerMessage>) {
+        let (sender, receiver) = mpsc::channel();
+        (lobby.connect(sender), receiver)
+    }
+
+    fn received(receiver: &Receiver<ServerMessage>) -> Vec<ServerMessage> {
+        receiver.try_iter().collect()
+    }
+
+    fn last_state(receiver: &Receiver<ServerMessage>) -> Snapshot {
+        received(receiver)
+            .into_iter()
+            .rev()
+            .find_map(|message| match message {
+                ServerMessage::State(snapshot) => Some(snapshot),
+                _ => None,
+            })
+            .expect("a state push")
+    }
+
+    fn errors(receiver: &Receiver<ServerMessage>) -> Vec<ServerError> {
+        received(receiver)
+            .into_iter()
+            .filter_map(|message| match message {
+                ServerMessage::Error(error) => Some(error),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn play(lobby: &mut Lobby, id: ClientId, notation: &str) {
+        let notation = notation.to_string();
+        lobby.handle(id, ClientMessage::Move { notation });
+    }
+
+    // A room called "game" with Player One and Two seated and their inboxes emptied
+    fn two_players() -> (Lobby, [(ClientId, Receiver<ServerMessage>); 2]) {
+        let mut lobby = Lobby::new();
+        let one = connect(&mut lobby);
+        let two = connect(&mut lobby);
+        let room = String::from("game");
+        lobby.handle(one.0, ClientMessage::Create { room: room.clone() });
+        lobby.handle(
+            two.0,
+            ClientMessage::Join {
+                room,
+                seat: None,
+                token: None,
+            },
+        );
+        received(&one.1);
+        received(&two.1);
+        (lobby, [one, two])
+    }
+
+    #[test]
+    fn seats_are_assigned_in_order_and_not_shared() {
+        let (mut lobby, _) = two_players();
+        let room = String::from("game");
+
+        let third = connect(&mut lobby);
+        lobby.handle(
+            third.0,
+            ClientMessage::Join {
+                room: room.clone(),
+                seat: None,
+                token: None,
+            },
+        );
+        assert_eq!(
+            errors(&third.1),
+            [ServerError::RoomFull { room: room.clone() }]
+        );
+        lobby.handle(
+            third.0,
+            ClientMessage::Join {
+                room: room.clone(),
+                seat: Some(PlayerID::One),
+                token: None,
+            },
+        );
+        assert_eq!(
+            errors(&third.1),
+            [ServerError::SeatTaken {
+                seat: PlayerID::One
+            }]
+        );
+
+        lobby.handle(third.0, ClientMessage::Watch { room: room.clone() });
+        let messages = received(&third.1);
+        assert_eq!(
+            messages[0],
+            ServerMessage::Joined {
+                room,
+                seat: None,
+                token: None
+            }
+        );
+        assert!(matches!(&messages[1], ServerMessage::State(snapshot)
+            if snapshot.seats == [true, true] && snapshot.spectators == 1));
+
+        lobby.handle(
+            third.0,
+            ClientMessage::Create {
+                room: String::from("game"),
+            },
+        );
+        assert_eq!(
+            errors(&third.1),
+            [ServerError::RoomExists {
+                room: String::from("game")
+            }]
+        );
+    }
+
+    #[test]
+    fn moves_are_checked_and_take_turns() {
+        let (mut lobby, [one, two]) = two_players();
+
+        play(&mut lobby, two.0, "C2");
+        assert_eq!(errors(&two.1), [ServerError::NotYourTurn]);
+        play(&mut lobby, one.0, "Z9");
+        assert_eq!(
+            errors(&one.1),
+            [ServerError::InvalidMove {
+                notation: String::from("Z9")
+            }]
+        );
+
+        play(&mut lobby, one.0, "C2");
+        let snapshot = last_state(&two.1);
+        assert_eq!(snapshot.moves, ["C2"]);
+        assert_eq!(snapshot.turn, PlayerID::Two);
+        assert_eq!(last_state(&one.1), snapshot);
+
+        // C2 was just played on, so nobody may play there this turn
+        play(&mut lobby, two.0, "C2");
+        assert_eq!(
+            errors(&two.1),
+            [ServerError::IllegalMove {
+                notation: String::from("C2")
+            }]
+        );
+        play(&mut lobby, one.0, "B1");
+        assert_eq!(errors(&one.1), [ServerError::NotYourTurn]);
+
+        let spectator = connect(&mut lobby);
+        lobby.handle(
+            spectator.0,
+            ClientMessage::Watch {
+                room: String::from("game"),
+            },
+        );
+        play(&mut lobby, spectator.0, "B1");
+        assert_eq!(errors(&spectator.1), [ServerError::NotSeated]);
+    }
+
+    #[test]
+    fn draw_offers_are_answered_by_the_opponent() {
+        let (mut lobby, [one, two]) = two_players();
+
+        lobby.handle(one.0, ClientMessage::AcceptDraw);
+        assert_eq!(errors(&one.1), [ServerError::NoDrawOffer]);
+        lobby.handle(one.0, ClientMessage::OfferDraw);
+        assert!(received(&two.1).contains(&ServerMessage::DrawOffered { by: PlayerID::One }));
+        // Only the other player can accept
+        lobby.handle(one.0, ClientMessage::AcceptDraw);
+        assert_eq!(errors(&one.1), [ServerError::NoDrawOffer]);
+
+        lobby.handle(two.0, ClientMessage::DeclineDraw);
+        assert!(received(&one.1).contains(&ServerMessage::DrawDeclined { by: PlayerID::Two }));
+        lobby.handle(two.0, ClientMessage::AcceptDraw);
+        assert_eq!(errors(&two.1), [ServerError::NoDrawOffer]);
+
+        lobby.handle(two.0, ClientMessage::OfferDraw);
+        lobby.handle(one.0, ClientMessage::AcceptDraw);
+        assert_eq!(last_state(&two.1).outcome, Some(Outcome::Draw));
+        play(&mut lobby, one.0, "C2");
+        assert_eq!(errors(&one.1), [ServerError::GameOver]);
+    }
+
+    #[test]
+    fn takebacks_undo_back_to_the_requesting_player() {
+        let (mut lobby, [one, two]) = two_players();
+
+        lobby.handle(one.0, ClientMessage::RequestTakeback);
+        assert_eq!(errors(&one.1), [ServerError::NothingToTakeBack]);
+
+        play(&mut lobby, one.0, "C2");
+        play(&mut lobby, two.0, "B1");
+        received(&one.1);
+        // Player One is to move, so their takeback also undoes the reply
+        lobby.handle(one.0, ClientMessage::RequestTakeback);
+        assert!(received(&two.1).contains(&ServerMessage::TakebackRequested { by: PlayerID::One }));
+        lobby.handle(one.0, ClientMessage::AcceptTakeback);
+        assert_eq!(errors(&one.1), [ServerError::NoTakebackRequest]);
+        lobby.handle(two.0, ClientMessage::AcceptTakeback);
+        let snapshot = last_state(&one.1);
+        assert!(snapshot.moves.is_empty());
+        assert_eq!(snapshot.turn, PlayerID::One);
+
+        // A move in the meantime cancels the request
+        play(&mut lobby, one.0, "C2");
+        lobby.handle(one.0, ClientMessage::RequestTakeback);
+        play(&mut lobby, two.0, "B1");
+        lobby.handle(two.0, ClientMessage::AcceptTakeback);
+        assert_eq!(errors(&two.1), [ServerError::NoTakebackRequest]);
+    }
+
+    fn joined_token(receiver: &Receiver<ServerMessage>) -> Option<String> {
+        received(receiver)
+            .into_iter()
+            .find_map(|message| match message {
+                ServerMessage::Joined { token, .. } => token,
+                _ => None,
+            })
+    }
+
+    fn join(lobby: &mut Lobby, id: ClientId, seat: Option<PlayerID>, token: Option<String>) {
+        let room = String::from("game");
+        lobby.handle(id, ClientMessage::Join { room, seat, token });
+    }
+
+    fn join_room(lobby: &mut Lobby, id: ClientId, room: &str, seat: Option<PlayerID>) {
+        let room = room.to_string();
+        lobby.handle(
+            id,
+            ClientMessage::Join {
+                room,
+                seat,
+                token: None,
+            },
+        );
+    }
+
+    #[test]
+    fn dropped_players_can_reclaim_their_seat() {
+        let mut lobby = Lobby::new();
+        let one = connect(&mut lobby);
+        let two = connect(&mut lobby);
+        let room = String::from("game");
+        lobby.handle(one.0, ClientMessage::Create { room: room.clone() });
+        join(&mut lobby, two.0, None, None);
+        let token = joined_token(&one.1).expect("a rejoin token");
+        assert_ne!(joined_token(&two.1), Some(token.clone()));
+        play(&mut lobby, one.0, "C2");
+        lobby.disconnect(one.0);
+        lobby.disconnect(two.0);
+
+        // The room outlives its members and the seats are kept for them
+        let stranger = connect(&mut lobby);
+        join(&mut lobby, stranger.0, None, None);
+        assert_eq!(
+            errors(&stranger.1),
+            [ServerError::RoomFull { room: room.clone() }]
+        );
+        let back = connect(&mut lobby);
+        join(&mut lobby, back.0, Some(PlayerID::One), Some(token.clone()));
+        let snapshot = last_state(&back.1);
+        assert_eq!(snapshot.moves, ["C2"]);
+        assert_eq!(snapshot.seats, [true, false]);
+
+        lobby.disconnect(back.0);
+        lobby.disconnect(stranger.0);
+        lobby.expire(Instant::now() + RECONNECT_GRACE);
+        let late = connect(&mut lobby);
+        join(&mut lobby, late.0, Some(PlayerID::One), Some(token));
+        assert_eq!(errors(&late.1), [ServerError::NoSuchRoom { room }]);
+    }
+
+    #[test]
+    fn reserved_seats_need_the_players_token() {
+        let (mut lobby, [one, two]) = two_players();
+        play(&mut lobby, one.0, "C2");
+        lobby.disconnect(two.0);
+
+        let stranger = connect(&mut lobby);
+        for token in [None, Some(String::from("guess"))] {
+            join(&mut lobby, stranger.0, Some(PlayerID::Two), token);
+            assert_eq!(
+                errors(&stranger.1),
+                [ServerError::SeatTaken {
+                    seat: PlayerID::Two
+                }]
+            );
+        }
+        // The stranger cannot move for the dropped player
+        play(&mut lobby, stranger.0, "B1");
+        assert_eq!(errors(&stranger.1), [ServerError::NotSeated]);
+        assert_eq!(last_state(&one.1).seats, [true, false]);
+    }
+
+    #[test]
+    fn a_token_takes_over_a_seat_from_a_stale_connection() {
+        let mut lobby = Lobby::new();
+        let one = connect(&mut lobby);
+        let two = connect(&mut lobby);
+        lobby.handle(
+            one.0,
+            ClientMessage::Create {
+                room: String::from("game"),
+            },
+        );
+        join(&mut lobby, two.0, None, None);
+        let token = joined_token(&one.1);
+        play(&mut lobby, one.0, "C2");
+        received(&two.1);
+
+        // The server has not noticed One's connection drop yet
+        let back = connect(&mut lobby);
+        join(&mut lobby, back.0, Some(PlayerID::One), None);
+        assert_eq!(
+            errors(&back.1),
+            [ServerError::SeatTaken {
+                seat: PlayerID::One
+            }]
+        );
+        join(&mut lobby, back.0, Some(PlayerID::One), token);
+        let snapshot = last_state(&back.1);
+        assert_eq!(snapshot.moves, ["C2"]);
+        assert_eq!(snapshot.seats, [true, true]);
+        assert_eq!(snapshot.spectators, 0);
+        received(&one.1);
+        assert_eq!(one.1.try_recv(), Err(mpsc::TryRecvError::Disconnected));
+
+        // The stale connection closing later leaves the new one seated
+        lobby.disconnect(one.0);
+        play(&mut lobby, two.0, "B1");
+        assert_eq!(last_state(&back.1).moves, ["C2", "B1"]);
+    }
+
+    #[test]
+    fn a_refused_join_keeps_the_old_seat() {
+        let (mut lobby, [one, two]) = two_players();
+        let guests = [connect(&mut lobby), connect(&mut lobby)];
+        let other = String::from("other");
+        lobby.handle(
+            guests[0].0,
+            ClientMessage::Create {
+                room: other.clone(),
+            },
+        );
+        lobby.handle(
+            guests[1].0,
+            ClientMessage::Join {
+                room: other.clone(),
+                seat: None,
+                token: None,
+            },
+        );
+
+        for seat in [None, Some(PlayerID::Two)] {
+            lobby.handle(
+                one.0,
+                ClientMessage::Join {
+                    room: other.clone(),
+                    seat,
+                    token: None,
+                },
+            );
+        }
+        assert_eq!(
+            errors(&one.1),
+            [
+                ServerError::RoomFull {
+                    room: other.clone()
+                },
+                ServerError::SeatTaken {
+                    seat: PlayerID::Two
+                }
+            ]
+        );
+        play(&mut lobby, one.0, "C2");
+        let snapshot = last_state(&two.1);
+        assert_eq!(snapshot.moves, ["C2"]);
+        assert_eq!(snapshot.seats, [true, true]);
+    }
+
+    #[test]
+    fn players_can_change_seats_in_their_own_room() {
+        let mut lobby = Lobby::new();
+        let solo = connect(&mut lobby);
+        let room = String::from("solo");
+        lobby.handle(solo.0, ClientMessage::Create { room: room.clone() });
+        let token = joined_token(&solo.1);
+
+        join_room(&mut lobby, solo.0, &room, Some(PlayerID::One));
+        let messages = received(&solo.1);
+        // Taking the same seat again keeps its token
+        assert!(messages.contains(&ServerMessage::Joined {
+            room: room.clone(),
+            seat: Some(PlayerID::One),
+            token: token.clone()
+        }));
+
+        join_room(&mut lobby, solo.0, &room, Some(PlayerID::Two));
+        let snapshot = last_state(&solo.1);
+        assert_eq!(snapshot.seats, [false, true]);
+        assert_eq!(snapshot.spectators, 0);
+
+        lobby.handle(solo.0, ClientMessage::Watch { room: room.clone() });
+        let snapshot = last_state(&solo.1);
+        assert_eq!(snapshot.seats, [false, false]);
+        assert_eq!(snapshot.spectators, 1);
+        join_room(&mut lobby, solo.0, &room, None);
+        assert_eq!(last_state(&solo.1).seats, [true, false]);
+    }
+}
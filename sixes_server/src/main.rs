@@ -0,0 +1,120 @@
+// Hosts Sixes games over WebSocket, see `sixes_protocol` for the message format.
+//
+//   sixes-server [address]     defaults to 127.0.0.1:7878
+use std::env;
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sixes_server::lobby::{ClientId, Lobby};
+use sixes_server::protocol::{decode, encode, ServerError, ServerMessage, DEFAULT_ADDR};
+use tungstenite::{Error, Message, WebSocket};
+
+// How long a connection blocks on reading before it flushes queued pushes
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+// A connection that has sent nothing for this long is pinged
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+// and one that has sent nothing, not even a pong, for this long is closed, so
+// half-open sockets give up their seat instead of holding it forever
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn main() -> io::Result<()> {
+    let addr = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDR.to_string());
+    let listener = TcpListener::bind(&addr)?;
+    println!("sixes-server listening on ws://{}", addr);
+
+    let lobby = Arc::new(Mutex::new(Lobby::new()));
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("connection failed: {}", e);
+                continue;
+            }
+        };
+        let lobby = Arc::clone(&lobby);
+        thread::spawn(move || {
+            if let Err(e) = serve(stream, lobby) {
+                eprintln!("connection closed: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+fn serve(stream: TcpStream, lobby: Arc<Mutex<Lobby>>) -> Result<(), Box<Error>> {
+    let socket = tungstenite::accept(stream).map_err(|e| match e {
+        tungstenite::HandshakeError::Failure(e) => e,
+        tungstenite::HandshakeError::Interrupted(_) => Error::ConnectionClosed,
+    })?;
+    socket
+        .get_ref()
+        .set_read_timeout(Some(POLL_INTERVAL))
+        .map_err(Error::Io)?;
+
+    let (sender, receiver) = mpsc::channel();
+    let id = lobby.lock().expect("lobby poisoned").connect(sender);
+    let result = run(socket, id, &lobby, receiver);
+    lobby.lock().expect("lobby poisoned").disconnect(id);
+    result
+}
+
+fn run(
+    mut socket: WebSocket<TcpStream>,
+    id: ClientId,
+    lobby: &Mutex<Lobby>,
+    receiver: Receiver<ServerMessage>,
+) -> Result<(), Box<Error>> {
+    let mut last_heard = Instant::now();
+    let mut last_ping = last_heard;
+    loop {
+        let read = socket.read();
+        if read.is_ok() {
+            last_heard = Instant::now();
+        }
+        match read {
+            Ok(Message::Text(text)) => match decode(&text) {
+                Ok(message) => lobby.lock().expect("lobby poisoned").handle(id, message),
+                Err(_) => socket.send(Message::Text(encode(&ServerMessage::Error(
+                    ServerError::Malformed,
+                ))))?,
+            },
+            Ok(Message::Close(_)) => return Ok(()),
+            Ok(_) => {}
+            Err(Error::Io(e))
+                if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut => {
+            }
+            Err(Error::ConnectionClosed) => return Ok(()),
+            Err(Error::Io(e)) if e.kind() == io::ErrorKind::ConnectionReset => return Ok(()),
+            Err(e) => return Err(Box::new(e)),
+        }
+
+        let quiet = last_heard.elapsed();
+        if quiet >= IDLE_TIMEOUT {
+            return Ok(());
+        }
+        if quiet >= PING_INTERVAL && last_ping.elapsed() >= PING_INTERVAL {
+            socket.write(Message::Ping(Vec::new()))?;
+            last_ping = Instant::now();
+        }
+
+        loop {
+            match receiver.try_recv() {
+                Ok(message) => socket.write(Message::Text(encode(&message)))?,
+                Err(TryRecvError::Empty) => break,
+                // The lobby handed this player's seat to a newer connection
+                Err(TryRecvError::Disconnected) => return Ok(()),
+            }
+        }
+        match socket.flush() {
+            Ok(()) => {}
+            Err(Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => {}
+            Err(e) => return Err(Box::new(e)),
+        }
+    }
+}
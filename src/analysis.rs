@@ -0,0 +1,105 @@
+use iced::futures::channel::mpsc as async_mpsc;
+use iced::futures::{SinkExt, StreamExt};
+use iced::{subscription, Color, Subscription};
+use sixes_game::engine::Engine;
+use sixes_game::eval::Weights;
+use sixes_game::{Coord, Play, Sixes};
+
+// Deepest search run in the background; each depth is reported as it finishes
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_DEPTH: u8 = 6;
+// Without threads the search blocks the page, so the browser stops earlier
+#[cfg(target_arch = "wasm32")]
+const MAX_DEPTH: u8 = 3;
+
+// Engine output for one position, from the side to move's point of view
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub moves: Vec<(Coord, Play)>,
+    pub depth: u8,
+    pub scores: Vec<(Coord, Play, f32)>,
+    pub line: Vec<(Coord, Play)>,
+}
+
+impl Report {
+    pub fn eval(&self) -> Option<f32> {
+        self.scores.first().map(|(_, _, score)| *score)
+    }
+
+    // Fill colours for the hexes that can be played on, from red for the
+    // worst move to green for the best. A hex with both a stone and a king
+    // move takes the better of the two.
+    pub fn shades(&self) -> Vec<(Coord, Color)> {
+        let (Some(best), Some(worst)) = (self.scores.first(), self.scores.last()) else {
+            return Vec::new();
+        };
+        let range = best.2 - worst.2;
+        let mut shades: Vec<(Coord, Color)> = Vec::new();
+        for (coord, _, score) in &self.scores {
+            if shades.iter().any(|(shaded, _)| shaded == coord) {
+                continue;
+            }
+            let t = if range > 0.0 {
+                (score - worst.2) / range
+            } else {
+                1.0
+            };
+            shades.push((*coord, Color::from_rgba(1.0 - t, t, 0.0, 0.45)));
+        }
+        shades
+    }
+}
+
+// Searches the position reached by `moves` with increasing depth for as long
+// as the subscription is active. The search runs on its own thread and stops
+// as soon as the app moves to another position.
+pub fn analyse(moves: Vec<(Coord, Play)>) -> Subscription<Report> {
+    subscription::channel(moves.clone(), 10, move |mut output| {
+        let moves = moves.clone();
+        async move {
+            let (reports, mut receiver) = async_mpsc::unbounded();
+            spawn_search(moves, reports);
+            loop {
+                match receiver.next().await {
+                    Some(report) => {
+                        let _ = output.send(report).await;
+                    }
+                    None => iced::futures::future::pending::<()>().await,
+                }
+            }
+        }
+    })
+}
+
+fn search(moves: &[(Coord, Play)], reports: &async_mpsc::UnboundedSender<Report>) {
+    let Ok(game) = Sixes::from_moves(moves) else {
+        return;
+    };
+    let weights = Weights::load_or_default();
+    for depth in 1..=MAX_DEPTH {
+        let engine = Engine::new(weights, depth);
+        // The receiver closes once the app has moved to another position
+        let Some(search) = engine.search(&game, &|| reports.is_closed()) else {
+            return;
+        };
+        let report = Report {
+            moves: moves.to_vec(),
+            depth,
+            scores: search.scores,
+            line: search.line,
+        };
+        if reports.unbounded_send(report).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_search(moves: Vec<(Coord, Play)>, reports: async_mpsc::UnboundedSender<Report>) {
+    std::thread::spawn(move || search(&moves, &reports));
+}
+
+#[cfg(target_arch = "wasm32")]
+fn spawn_search(moves: Vec<(Coord, Play)>, reports: async_mpsc::UnboundedSender<Report>) {
+    search(&moves, &reports);
+}
@@ -0,0 +1,232 @@
+use iced::time::{Duration, Instant};
+use iced::widget::canvas::{Frame, Stroke};
+use iced::{Color, Point, Rectangle};
+use sixes_game::{Coord, Play, PlayerID, Sixes};
+
+use crate::hexagon::HexagonGrid;
+use crate::palette::Palette;
+use crate::pieces::{draw_part, piece_parts, Part};
+use crate::player::Player;
+
+const MOVE_DURATION: Duration = Duration::from_millis(400);
+const WIN_DURATION: Duration = Duration::from_secs(3);
+// Times the winning triple pulses over `WIN_DURATION`
+const WIN_PULSES: f32 = 6.0;
+
+// The part with its fill and outline made see-through by `alpha`
+fn faded(part: Part, alpha: f32) -> Part {
+    let fade = |color: Color| Color {
+        a: color.a * alpha,
+        ..color
+    };
+    Part {
+        fill: part.fill.map(fade),
+        outline: part.outline.map(fade),
+        ..part
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Kind {
+    // A move landing on `coord`; `captured` opposing stones make way for it
+    Move {
+        coord: Coord,
+        play: Play,
+        player: PlayerID,
+        stones: u8,
+        captured: u8,
+    },
+    Win {
+        triples: Vec<[Coord; 3]>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Animation {
+    kind: Kind,
+    started: Instant,
+    progress: f32,
+}
+
+impl Animation {
+    // Animates the last move of `game`, if it has one
+    pub fn last_move(game: &Sixes, now: Instant) -> Option<Self> {
+        let history = game.history();
+        let (&(coord, play), earlier) = history.split_last()?;
+        let before = Sixes::from_moves(earlier).ok()?;
+        let preview = before.dry_run(coord, play).ok()?;
+        Some(Animation {
+            kind: Kind::Move {
+                coord,
+                play,
+                player: before.turn(),
+                stones: preview.stack,
+                captured: preview.removed,
+            },
+            started: now,
+            progress: 0.0,
+        })
+    }
+
+    // Pulses the triples that won the game
+    pub fn win(game: &Sixes, now: Instant) -> Option<Self> {
+        let winner = game.game_state().winner()?;
+        let triples: Vec<[Coord; 3]> = game
+            .triples()
+            .iter()
+            .filter(|triple| triple.player() == winner)
+            .map(|triple| triple.coords())
+            .collect();
+        if triples.is_empty() {
+            return None;
+        }
+        Some(Animation {
+            kind: Kind::Win { triples },
+            started: now,
+            progress: 0.0,
+        })
+    }
+
+    fn duration(&self) -> Duration {
+        match self.kind {
+            Kind::Move { .. } => MOVE_DURATION,
+            Kind::Win { .. } => WIN_DURATION,
+        }
+    }
+
+    // Advances to `now`, returning false once the animation has finished
+    pub fn tick(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.started);
+        self.progress = (elapsed.as_secs_f32() / self.duration().as_secs_f32()).min(1.0);
+        self.progress < 1.0
+    }
+
+    pub fn is_move(&self) -> bool {
+        matches!(self.kind, Kind::Move { .. })
+    }
+
+    // The hex whose pieces the animation draws instead of the board
+    pub fn coord(&self) -> Option<Coord> {
+        match self.kind {
+            Kind::Move { coord, .. } => Some(coord),
+            Kind::Win { .. } => None,
+        }
+    }
+
+    pub fn draw(
+        &self,
+        grid: &HexagonGrid,
+        bounds: Rectangle,
+        player_one: Player,
+        player_two: Player,
+        top: PlayerID,
+        palette: &Palette,
+    ) -> Frame {
+        let mut frame = Frame::new(bounds.size());
+        let t = self.progress;
+        match self.kind {
+            Kind::Move {
+                coord,
+                play,
+                player,
+                stones,
+                captured,
+            } => {
+                let (color, other_color) = match player {
+                    PlayerID::One => (player_one.color(), player_two.color()),
+                    PlayerID::Two => (player_two.color(), player_one.color()),
+                };
+                let target = grid.center(bounds.size(), coord);
+                let radius = grid.radius(bounds.size());
+                // Player Two's pieces are ringed as on the board
+                let mark = |owner: PlayerID| {
+                    (palette.marked && owner == player_two.id()).then_some(player_one.color())
+                };
+
+                // Captured stones fade out where they stood, except under a
+                // king, which sits on top of them
+                if captured > 0 && play == Play::Stones {
+                    let parts = piece_parts(
+                        captured,
+                        false,
+                        other_color,
+                        color,
+                        mark(player.other()),
+                        target,
+                        radius,
+                    );
+                    for part in parts {
+                        draw_part(&mut frame, faded(part, 1.0 - t));
+                    }
+                }
+
+                // Eased so pieces slow down as they land
+                let eased = 1.0 - (1.0 - t) * (1.0 - t);
+                match play {
+                    // Stones slide in from the mover's side of the board
+                    Play::Stones => {
+                        let start = if player == top {
+                            Point::new(bounds.width / 2.0, 0.0)
+                        } else {
+                            Point::new(bounds.width / 2.0, bounds.height)
+                        };
+                        let center = Point::new(
+                            start.x + (target.x - start.x) * eased,
+                            start.y + (target.y - start.y) * eased,
+                        );
+                        let parts = piece_parts(
+                            stones,
+                            false,
+                            color,
+                            other_color,
+                            mark(player),
+                            center,
+                            radius,
+                        );
+                        for part in parts {
+                            draw_part(&mut frame, part);
+                        }
+                    }
+                    // The king drops onto the hex, shrinking to its resting size.
+                    // The stack it captured stays put underneath, unringed as
+                    // stones under a king are on the board.
+                    Play::King => {
+                        let stack =
+                            piece_parts(captured, false, other_color, color, None, target, radius);
+                        let scale = 1.0 + 2.0 * (1.0 - eased);
+                        let king = piece_parts(
+                            0,
+                            true,
+                            color,
+                            other_color,
+                            mark(player),
+                            target,
+                            radius * scale,
+                        );
+                        for part in stack {
+                            draw_part(&mut frame, part);
+                        }
+                        for part in king {
+                            draw_part(&mut frame, faded(part, eased));
+                        }
+                    }
+                }
+            }
+            Kind::Win { ref triples } => {
+                let pulse = (t * WIN_PULSES * std::f32::consts::TAU).sin() * 0.5 + 0.5;
+                for coords in triples {
+                    frame.stroke(
+                        &grid.draw_line(bounds.size(), *coords),
+                        Stroke::default()
+                            .with_width(8.0 + 10.0 * pulse)
+                            .with_color(Color {
+                                a: 0.4 + 0.5 * pulse,
+                                ..palette.win_pulse
+                            }),
+                    );
+                }
+            }
+        }
+        frame
+    }
+}
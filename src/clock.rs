@@ -0,0 +1,68 @@
+use std::fmt;
+use std::time::Duration;
+
+use sixes_game::clock::{Clock, TimeControl};
+use sixes_game::PlayerID;
+
+use crate::locale::tr;
+
+// Time control presets offered in the menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockSetting {
+    Untimed,
+    Blitz,
+    Rapid,
+    Byoyomi,
+}
+
+impl ClockSetting {
+    pub const ALL: [ClockSetting; 4] = [
+        ClockSetting::Untimed,
+        ClockSetting::Blitz,
+        ClockSetting::Rapid,
+        ClockSetting::Byoyomi,
+    ];
+
+    pub fn control(&self) -> Option<TimeControl> {
+        match self {
+            ClockSetting::Untimed => None,
+            ClockSetting::Blitz => Some(TimeControl::SuddenDeath {
+                base: Duration::from_secs(5 * 60),
+            }),
+            ClockSetting::Rapid => Some(TimeControl::Fischer {
+                base: Duration::from_secs(10 * 60),
+                increment: Duration::from_secs(5),
+            }),
+            ClockSetting::Byoyomi => Some(TimeControl::Byoyomi {
+                base: Duration::from_secs(5 * 60),
+                period: Duration::from_secs(30),
+                periods: 3,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for ClockSetting {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            ClockSetting::Untimed => tr("clock-untimed"),
+            ClockSetting::Blitz => tr("clock-blitz"),
+            ClockSetting::Rapid => tr("clock-rapid"),
+            ClockSetting::Byoyomi => tr("clock-byoyomi"),
+        };
+        write!(f, "{}", label)
+    }
+}
+
+// Remaining time as m:ss, with the periods left once a player is in byoyomi
+pub fn clock_text(clock: &Clock, player: PlayerID) -> String {
+    // Round up so the display only reaches 0:00 when the flag falls
+    let remaining = clock.remaining(player);
+    let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    let time = format!("{}:{:02}", seconds / 60, seconds % 60);
+    if clock.in_byoyomi(player) {
+        format!("{} ({})", time, clock.periods(player))
+    } else {
+        time
+    }
+}
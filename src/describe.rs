@@ -0,0 +1,125 @@
+// Plain-text descriptions of the game for players who cannot see the board
+use sixes_game::{Coord, GameState, Play, PlayerID, Sixes};
+
+use crate::locale::{player as name, tr, tr_args};
+
+fn stones(count: u8) -> String {
+    tr_args("stones", &[("count", count.into())])
+}
+
+pub fn describe_hex(game: &Sixes, coord: Coord) -> String {
+    let board = game.board();
+    let Some((_, count, king, owner)) = board.iter().find(|(hex, _, _, _)| *hex == coord) else {
+        return coord.to_string();
+    };
+    let hex = coord.to_string();
+    match owner {
+        Some(player) if *king => tr_args(
+            "hex-king",
+            &[("hex", hex.into()), ("player", name(*player).into())],
+        ),
+        Some(player) => tr_args(
+            "hex-stones",
+            &[
+                ("hex", hex.into()),
+                ("stones", stones(*count).into()),
+                ("player", name(*player).into()),
+            ],
+        ),
+        None => tr_args("hex-empty", &[("hex", hex.into())]),
+    }
+}
+
+// Whose turn it is, what each player has in hand and every occupied hex
+pub fn describe_board(game: &Sixes) -> String {
+    let status = match game.game_state() {
+        GameState::Running => tr_args("board-to-move", &[("player", name(game.turn()).into())]),
+        GameState::Win(player) => tr_args("board-won", &[("player", name(player).into())]),
+        GameState::WinOnTime(player) => {
+            tr_args("board-won-on-time", &[("player", name(player).into())])
+        }
+    };
+    let hands = game
+        .players()
+        .iter()
+        .map(|(player, count, king)| {
+            let id = if *king { "hand-king" } else { "hand-no-king" };
+            tr_args(
+                id,
+                &[
+                    ("player", name(*player).into()),
+                    ("stones", stones(*count).into()),
+                ],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let occupied = game
+        .board()
+        .iter()
+        .filter(|(_, _, _, owner)| owner.is_some())
+        .map(|(coord, _, _, _)| describe_hex(game, *coord))
+        .collect::<Vec<_>>();
+    let occupied = if occupied.is_empty() {
+        tr("board-empty")
+    } else {
+        format!("{}.", occupied.join("; "))
+    };
+    let blocked = match game.last_captured() {
+        Some(coord) if game.game_state() == GameState::Running => format!(
+            " {}",
+            tr_args("board-blocked", &[("hex", coord.to_string().into())])
+        ),
+        _ => String::new(),
+    };
+    format!("{} {} {}{}", status, hands, occupied, blocked)
+}
+
+pub fn describe_last_move(game: &Sixes) -> String {
+    let history = game.history();
+    let Some((&(coord, play), earlier)) = history.split_last() else {
+        return tr("no-moves-yet");
+    };
+    let Some(preview) = Sixes::from_moves(earlier)
+        .ok()
+        .and_then(|before| before.dry_run(coord, play).ok())
+    else {
+        return String::new();
+    };
+    let player = if history.len() % 2 == 1 {
+        PlayerID::One
+    } else {
+        PlayerID::Two
+    };
+    let (player, hex) = (name(player), coord.to_string());
+    let mut text = match play {
+        Play::Stones => tr_args(
+            "last-stones",
+            &[
+                ("player", player.into()),
+                ("hex", hex.into()),
+                ("stones", stones(preview.stack).into()),
+            ],
+        ),
+        Play::King => tr_args(
+            "last-king",
+            &[("player", player.into()), ("hex", hex.into())],
+        ),
+    };
+    if preview.removed > 0 {
+        let capturing = tr_args(
+            "last-capturing",
+            &[("stones", stones(preview.removed).into())],
+        );
+        text.push_str(&format!(", {}", capturing));
+    }
+    if preview.bonus > 0 {
+        let bonus = tr_args("last-bonus", &[("stones", stones(preview.bonus).into())]);
+        text.push_str(&format!(" {}", bonus));
+    }
+    if !preview.triples.is_empty() {
+        text.push_str(&format!(", {}", tr("last-triple")));
+    }
+    text.push('.');
+    text
+}
@@ -1,22 +1,27 @@
-use crate::pieces::draw_stones;
+use crate::palette::Palette;
+use crate::pieces::{draw_part, piece_parts};
 use crate::player::Player;
-use iced::widget::canvas::{path::Builder, Frame, Path, Stroke};
-use iced::{Color, Point, Rectangle};
+use iced::widget::canvas::{path::Builder, Frame, Path, Stroke, Text};
+use iced::{alignment, Color, Point, Rectangle, Size};
 use sixes_game::PlayerID;
 use sixes_game::{Coord, CENTER_HEX};
 
-#[derive(Debug, Clone)]
-struct Line {
-    start: Point,
-    end: Point,
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum Direction {
-    Collinear,
-    Clockwise,
-    CounterClockwise,
-}
+// Axial coordinates (q, r) of each hex. Hexagons are pointy-topped, q runs
+// east along a row and r runs down to the south-east, so A1 is the origin and
+// B3 sits one step west of it on the third row.
+const AXIAL: [(i32, i32, Coord); 11] = [
+    (0, 0, Coord::A1),
+    (1, 0, Coord::B1),
+    (2, 0, Coord::C1),
+    (3, 0, Coord::D1),
+    (0, 1, Coord::B2),
+    (1, 1, Coord::C2),
+    (2, 1, Coord::D2),
+    (-1, 2, Coord::B3),
+    (0, 2, Coord::C3),
+    (1, 2, Coord::D3),
+    (2, 2, Coord::E3),
+];
 
 #[derive(Debug, Clone)]
 pub struct HexagonUI {
@@ -30,60 +35,67 @@ pub struct HexagonUI {
 pub struct HexagonGrid {
     padding_x: f32,
     padding_y: f32,
+    // Clockwise turns of the board in 60 degree steps
+    rotation: u8,
 }
 
-fn direction(p1: Point, p2: Point, p3: Point) -> Direction {
-    let val = (p2.y - p1.y) * (p3.x - p2.x) - (p2.x - p1.x) * (p3.y - p2.y);
-
-    if val == 0.0 {
-        return Direction::Collinear;
-    } else if val > 0.0 {
-        return Direction::Clockwise;
-    } else {
-        return Direction::CounterClockwise;
-    }
+// Centre of the hex at axial (q, r), in units of the hexagon radius
+fn axial_to_unit(q: f32, r: f32) -> (f32, f32) {
+    (3.0f32.sqrt() * (q + r / 2.0), 1.5 * r)
 }
 
-impl Line {
-    fn on_line(&self, p: Point) -> bool {
-        // Check whether p is on the line or not
-        if p.x <= self.start.x.max(self.end.x)
-            && p.x <= self.start.x.min(self.end.x)
-            && (p.y <= self.start.y.max(self.end.y) && p.y <= self.start.y.min(self.end.y))
-        {
-            return true;
-        }
-
-        false
+// The hex containing a point given in units of the hexagon radius. Rounding
+// in cube coordinates picks the hex whose centre is nearest, which for a
+// regular tiling is exactly the hex the point lies in.
+fn unit_to_axial(x: f32, y: f32) -> (i32, i32) {
+    let r = y / 1.5;
+    let q = x / 3.0f32.sqrt() - r / 2.0;
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
     }
+    (rq as i32, rr as i32)
+}
 
-    fn does_intersect(&self, other: &Line) -> bool {
-        let dir1 = direction(self.start, self.end, other.start);
-        let dir2 = direction(self.start, self.end, other.end);
-        let dir3 = direction(other.start, other.end, self.start);
-        let dir4 = direction(other.start, other.end, self.end);
-
-        if dir1 != dir2 && dir3 != dir4 {
-            return true;
-        }
-
-        if dir1 == Direction::Collinear && self.on_line(other.start) {
-            return true;
-        }
+fn coord_position(coord: Coord) -> (f32, f32) {
+    AXIAL
+        .iter()
+        .find(|(_, _, hex)| *hex == coord)
+        .map(|(q, r, _)| axial_to_unit(*q as f32, *r as f32))
+        .unwrap_or((0.0, 0.0))
+}
 
-        if dir2 == Direction::Collinear && self.on_line(other.end) {
-            return true;
-        }
+// Turns a unit position clockwise on screen about A1's centre
+fn rotate((x, y): (f32, f32), degrees: f32) -> (f32, f32) {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    (x * cos - y * sin, x * sin + y * cos)
+}
 
-        if dir3 == Direction::Collinear && other.on_line(self.start) {
-            return true;
-        }
+// Where the board sits in a canvas: the hexagon radius in pixels and the
+// pixel position of the unit origin, the centre of A1 whatever the rotation
+#[derive(Debug, Clone, Copy)]
+struct Layout {
+    radius: f32,
+    origin: Point,
+}
 
-        if dir4 == Direction::Collinear && other.on_line(self.end) {
-            return true;
-        }
+impl Layout {
+    fn pixel(&self, (x, y): (f32, f32)) -> Point {
+        Point::new(
+            self.origin.x + x * self.radius,
+            self.origin.y + y * self.radius,
+        )
+    }
 
-        false
+    fn unit_position(&self, point: Point) -> (f32, f32) {
+        (
+            (point.x - self.origin.x) / self.radius,
+            (point.y - self.origin.y) / self.radius,
+        )
     }
 }
 
@@ -101,12 +113,12 @@ impl HexagonUI {
         let mut coordinates = [Point::new(0.0, 0.0); 6];
         let angle_degrees = 60.0;
 
-        for i in 0..6 {
+        for (i, coordinate) in coordinates.iter_mut().enumerate() {
             let angle = (angle_degrees * i as f32 + self.rotation).to_radians();
             let x = self.center.x + self.radius * angle.cos();
             let y = self.center.y + self.radius * angle.sin();
 
-            coordinates[i] = Point::new(x, y);
+            *coordinate = Point::new(x, y);
         }
 
         coordinates
@@ -126,32 +138,6 @@ impl HexagonUI {
         builder.close();
         builder.build()
     }
-
-    pub fn contains(&self, point: Point) -> bool {
-        let vertices = self.get_coordinates();
-        let ex_line = Line {
-            start: point,
-            end: Point::new(100000.0, point.y),
-        };
-
-        let mut count = 0;
-        for i in 0..6 {
-            let line = Line {
-                start: vertices[i],
-                end: vertices[(i + 1) % 6],
-            };
-
-            if line.does_intersect(&ex_line) {
-                if direction(line.start, point, line.end) == Direction::Collinear {
-                    return line.on_line(point);
-                }
-
-                count += 1;
-            }
-        }
-
-        count % 2 == 1
-    }
 }
 
 impl HexagonGrid {
@@ -159,63 +145,135 @@ impl HexagonGrid {
         HexagonGrid {
             padding_x,
             padding_y,
+            rotation: 0,
         }
     }
 
-    fn create_hexagons(&self, width: f32) -> Vec<HexagonUI> {
-        let mut hexagons = Vec::new();
-
-        let hexagon_centers = [
-            (1.0, 1.0, Coord::A1),
-            (3.0, 1.0, Coord::B1),
-            (5.0, 1.0, Coord::C1),
-            (7.0, 1.0, Coord::D1),
-            (2.0, 5.0 / 2.0, Coord::B2),
-            (4.0, 5.0 / 2.0, Coord::C2),
-            (6.0, 5.0 / 2.0, Coord::D2),
-            (1.0, 4.0, Coord::B3),
-            (3.0, 4.0, Coord::C3),
-            (5.0, 4.0, Coord::D3),
-            (7.0, 4.0, Coord::E3),
-        ];
-
-        let hexagon_width = (width - self.padding_x * 2.0) / 8.0;
-        let hexagon_height = (hexagon_width / 3.0f32.sqrt()) * 2.0;
-
-        for (x, y, coord) in hexagon_centers.iter() {
-            let hexagon = HexagonUI::new(
-                Point::new(
-                    (x * hexagon_width) + self.padding_x,
-                    (y * hexagon_height) + self.padding_y,
-                ),
-                hexagon_height,
-                30.0,
-                coord.clone(),
-            );
+    pub fn rotated(self, steps: u8) -> Self {
+        HexagonGrid {
+            rotation: steps % 6,
+            ..self
+        }
+    }
 
-            hexagons.push(hexagon);
+    fn degrees(&self) -> f32 {
+        60.0 * self.rotation as f32
+    }
+
+    // Centre of a hex in units of the hexagon radius, as turned on screen.
+    // Turning by whole 60 degree steps keeps the hexagons pointy-topped.
+    fn position(&self, coord: Coord) -> (f32, f32) {
+        rotate(coord_position(coord), self.degrees())
+    }
+
+    // The nearest hex from `from` roughly in the direction `degrees`, measured
+    // clockwise from east as on screen. Adjacent hexes lie every 60 degrees.
+    pub fn step(&self, from: Coord, degrees: f32) -> Option<Coord> {
+        let (x, y) = self.position(from);
+        let (dx, dy) = (degrees.to_radians().cos(), degrees.to_radians().sin());
+        AXIAL
+            .iter()
+            .filter_map(|(_, _, coord)| {
+                let (cx, cy) = self.position(*coord);
+                let distance = ((cx - x).powi(2) + (cy - y).powi(2)).sqrt();
+                let alignment = ((cx - x) * dx + (cy - y) * dy) / distance;
+                // Within 40 degrees either side of the direction
+                (distance > 0.0 && alignment > 40.0f32.to_radians().cos())
+                    .then_some((*coord, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(coord, _)| coord)
+    }
+
+    // Scales the board to fit whichever dimension of `size` is tighter and
+    // centres it. Drawing and hit-testing both go through this.
+    fn layout(&self, size: Size) -> Layout {
+        let positions = AXIAL.map(|(_, _, coord)| self.position(coord));
+        let half_width = 3.0f32.sqrt() / 2.0;
+        let min_x = positions.iter().map(|p| p.0).fold(f32::MAX, f32::min) - half_width;
+        let max_x = positions.iter().map(|p| p.0).fold(f32::MIN, f32::max) + half_width;
+        let min_y = positions.iter().map(|p| p.1).fold(f32::MAX, f32::min) - 1.0;
+        let max_y = positions.iter().map(|p| p.1).fold(f32::MIN, f32::max) + 1.0;
+
+        let across = (size.width - self.padding_x * 2.0) / (max_x - min_x);
+        let down = (size.height - self.padding_y * 2.0) / (max_y - min_y);
+        let radius = across.min(down).max(0.0);
+        Layout {
+            radius,
+            origin: Point::new(
+                size.width / 2.0 - radius * (min_x + max_x) / 2.0,
+                size.height / 2.0 - radius * (min_y + max_y) / 2.0,
+            ),
         }
+    }
 
-        hexagons
+    // Centre-to-corner size of each hexagon
+    pub fn radius(&self, size: Size) -> f32 {
+        self.layout(size).radius
     }
 
-    pub fn draw_hexes(&self, bounds: Rectangle) -> Frame {
+    fn create_hexagons(&self, size: Size) -> Vec<HexagonUI> {
+        let layout = self.layout(size);
+        AXIAL
+            .iter()
+            .map(|(_, _, coord)| {
+                HexagonUI::new(
+                    layout.pixel(self.position(*coord)),
+                    layout.radius,
+                    30.0,
+                    *coord,
+                )
+            })
+            .collect()
+    }
+
+    pub fn draw_hexes(&self, bounds: Rectangle, palette: &Palette, labels: bool) -> Frame {
         let mut frame = Frame::new(bounds.size());
-        let hexagons = self.create_hexagons(bounds.width);
+        let hexagons = self.create_hexagons(bounds.size());
 
         for hexagon in hexagons.iter() {
             if hexagon.coord == CENTER_HEX {
-                frame.fill(&hexagon.draw(), Color::from_rgb(1.0, 1.0, 0.8));
+                frame.fill(&hexagon.draw(), palette.centre);
+            }
+            frame.stroke(
+                &hexagon.draw(),
+                Stroke::default()
+                    .with_width(2.0)
+                    .with_color(palette.board_line),
+            );
+            // Same names as the move notation, near the top of the hex
+            if labels {
+                frame.fill_text(Text {
+                    content: hexagon.coord.to_string(),
+                    position: Point::new(hexagon.center.x, hexagon.center.y - hexagon.radius * 0.7),
+                    color: palette.board_line,
+                    size: (hexagon.radius / 5.0).max(10.0),
+                    horizontal_alignment: alignment::Horizontal::Center,
+                    vertical_alignment: alignment::Vertical::Center,
+                    ..Text::default()
+                });
+            }
+        }
+
+        frame
+    }
+
+    pub fn shade_hexes(&self, bounds: Rectangle, shades: &[(Coord, Color)]) -> Frame {
+        let mut frame = Frame::new(bounds.size());
+        let hexagons = self.create_hexagons(bounds.size());
+
+        for hexagon in hexagons.iter() {
+            if let Some((_, color)) = shades.iter().find(|(coord, _)| *coord == hexagon.coord) {
+                frame.fill(&hexagon.draw(), *color);
             }
-            frame.stroke(&hexagon.draw(), Stroke::default().with_width(2.0));
         }
 
         frame
     }
 
-    pub fn draw_circles(&self, board_width: f32, hexes_selected: Vec<Coord>) -> Vec<Path> {
+    pub fn draw_circles(&self, size: Size, hexes_selected: Vec<Coord>) -> Vec<Path> {
         let mut paths = Vec::new();
-        let hexagons = self.create_hexagons(board_width);
+        let hexagons = self.create_hexagons(size);
 
         for hexagon in hexagons.iter() {
             if hexes_selected.contains(&hexagon.coord) {
@@ -226,15 +284,38 @@ impl HexagonGrid {
         paths
     }
 
+    // Corners of a hex in pixels, for drawing without a canvas
+    pub fn corners(&self, size: Size, coord: Coord) -> [Point; 6] {
+        let layout = self.layout(size);
+        HexagonUI::new(
+            layout.pixel(self.position(coord)),
+            layout.radius,
+            30.0,
+            coord,
+        )
+        .get_coordinates()
+    }
+
+    pub fn center(&self, size: Size, coord: Coord) -> Point {
+        self.layout(size).pixel(self.position(coord))
+    }
+
+    // Line from the centre of the first hex of a triple to the last
+    pub fn draw_line(&self, size: Size, coords: [Coord; 3]) -> Path {
+        Path::line(self.center(size, coords[0]), self.center(size, coords[2]))
+    }
+
     pub fn draw_pieces(
         &self,
         bounds: Rectangle,
         board: Vec<(Coord, u8, bool, Option<PlayerID>)>,
         player_one: Player,
         player_two: Player,
+        ghost: Option<Coord>,
+        marked: bool,
     ) -> Frame {
         let mut frame = Frame::new(bounds.size());
-        let hexagons = self.create_hexagons(bounds.width);
+        let hexagons = self.create_hexagons(bounds.size());
 
         for hexagon in hexagons.iter() {
             let hex_data = board
@@ -244,21 +325,29 @@ impl HexagonGrid {
             if let Some(player_id) = hex_data.and_then(|(_, _, _, player_id)| *player_id) {
                 let stones = hex_data.map(|(_, stones, _, _)| *stones).unwrap_or(0);
                 let king = hex_data.map(|(_, _, king, _)| *king).unwrap_or(false);
-                let player_color = if player_id == player_one.id() {
-                    player_one.color()
+                let (mut player_color, mut other_color) = if player_id == player_one.id() {
+                    (player_one.color(), player_two.color())
                 } else {
-                    player_two.color()
+                    (player_two.color(), player_one.color())
                 };
-                if king {
-                    let path = Path::circle(hexagon.center, hexagon.radius / 4.0);
-                    frame.fill(&path, player_color);
-                    frame.stroke(&path, Stroke::default().with_width(2.0));
-                } else if stones > 0 {
-                    let paths = draw_stones(stones, hexagon.center, hexagon.radius);
-                    for path in paths {
-                        frame.fill(&path, player_color);
-                        frame.stroke(&path, Stroke::default().with_width(2.0));
-                    }
+                // A previewed move is drawn see-through
+                if ghost == Some(hexagon.coord) {
+                    player_color.a = 0.5;
+                    other_color.a = 0.5;
+                }
+                // Player Two's pieces get an inner ring in Player One's colour
+                let mark = (marked && player_id == player_two.id()).then_some(player_one.color());
+                let parts = piece_parts(
+                    stones,
+                    king,
+                    player_color,
+                    other_color,
+                    mark,
+                    hexagon.center,
+                    hexagon.radius,
+                );
+                for part in parts {
+                    draw_part(&mut frame, part);
                 }
             }
         }
@@ -266,13 +355,167 @@ impl HexagonGrid {
         frame
     }
 
-    pub fn in_hexagon(&self, point: Point, board_width: f32) -> Option<Coord> {
-        let hexagons = self.create_hexagons(board_width);
-        for hexagon in hexagons.iter() {
-            if hexagon.contains(point) {
-                return Some(hexagon.coord.clone());
+    pub fn in_hexagon(&self, point: Point, size: Size) -> Option<Coord> {
+        let layout = self.layout(size);
+        if layout.radius <= 0.0 {
+            return None;
+        }
+        let (x, y) = rotate(layout.unit_position(point), -self.degrees());
+        let (q, r) = unit_to_axial(x, y);
+        AXIAL
+            .iter()
+            .find(|(hex_q, hex_r, _)| (*hex_q, *hex_r) == (q, r))
+            .map(|(_, _, coord)| *coord)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZES: [Size; 4] = [
+        Size::new(800.0, 600.0),
+        Size::new(2000.0, 300.0),
+        Size::new(300.0, 2000.0),
+        Size::new(97.0, 71.0),
+    ];
+
+    // A point a fraction of the way from a hex's centre towards one of its
+    // corners, counted clockwise from the lower right one; corner 4 is the top
+    fn towards_corner(
+        grid: &HexagonGrid,
+        size: Size,
+        coord: Coord,
+        corner: usize,
+        t: f32,
+    ) -> Point {
+        let center = grid.center(size, coord);
+        let angle = (60.0 * corner as f32 + 30.0).to_radians();
+        let distance = grid.radius(size) * t;
+        Point::new(
+            center.x + distance * angle.cos(),
+            center.y + distance * angle.sin(),
+        )
+    }
+
+    #[test]
+    fn centres_hit_their_own_hex() {
+        let grid = HexagonGrid::new(5.0, 5.0);
+        for size in SIZES {
+            for coord in Coord::ALL {
+                assert_eq!(grid.in_hexagon(grid.center(size, coord), size), Some(coord));
             }
         }
-        None
+    }
+
+    #[test]
+    fn points_near_corners_stay_in_the_hex() {
+        let grid = HexagonGrid::new(5.0, 5.0);
+        for size in SIZES {
+            for coord in Coord::ALL {
+                for corner in 0..6 {
+                    let point = towards_corner(&grid, size, coord, corner, 0.97);
+                    assert_eq!(
+                        grid.in_hexagon(point, size),
+                        Some(coord),
+                        "{:?} corner {}",
+                        coord,
+                        corner
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn points_near_edges_stay_in_the_hex() {
+        let grid = HexagonGrid::new(5.0, 5.0);
+        let size = SIZES[0];
+        let apothem = grid.radius(size) * 3.0f32.sqrt() / 2.0;
+        for coord in Coord::ALL {
+            let center = grid.center(size, coord);
+            for side in 0..6 {
+                let angle = (60.0 * side as f32).to_radians();
+                let point = Point::new(
+                    center.x + apothem * 0.97 * angle.cos(),
+                    center.y + apothem * 0.97 * angle.sin(),
+                );
+                assert_eq!(
+                    grid.in_hexagon(point, size),
+                    Some(coord),
+                    "{:?} side {}",
+                    coord,
+                    side
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn shared_edges_resolve_to_a_neighbour() {
+        let grid = HexagonGrid::new(5.0, 5.0);
+        let size = SIZES[0];
+        let a1 = grid.center(size, Coord::A1);
+        let b1 = grid.center(size, Coord::B1);
+        let midpoint = Point::new((a1.x + b1.x) / 2.0, (a1.y + b1.y) / 2.0);
+        let hit = grid.in_hexagon(midpoint, size);
+        assert!(
+            hit == Some(Coord::A1) || hit == Some(Coord::B1),
+            "{:?}",
+            hit
+        );
+    }
+
+    #[test]
+    fn outside_the_board_misses() {
+        let grid = HexagonGrid::new(5.0, 5.0);
+        for size in SIZES {
+            // Padding in the canvas corners
+            assert_eq!(grid.in_hexagon(Point::ORIGIN, size), None);
+            assert_eq!(
+                grid.in_hexagon(Point::new(size.width, size.height), size),
+                None
+            );
+            // Just beyond the top corner of A1 and the lower right corner of D1
+            let above = towards_corner(&grid, size, Coord::A1, 4, 1.05);
+            assert_eq!(grid.in_hexagon(above, size), None);
+            let beside = towards_corner(&grid, size, Coord::D1, 0, 1.05);
+            assert_eq!(grid.in_hexagon(beside, size), None);
+            // The gaps either end of the middle row, where the rows above and below stick out
+            let west = grid.center(size, Coord::B2);
+            let gap = Point::new(west.x - grid.radius(size) * 3.0f32.sqrt(), west.y);
+            assert_eq!(grid.in_hexagon(gap, size), None);
+            let east = grid.center(size, Coord::D2);
+            let gap = Point::new(east.x + grid.radius(size) * 3.0f32.sqrt(), east.y);
+            assert_eq!(grid.in_hexagon(gap, size), None);
+        }
+    }
+
+    #[test]
+    fn rotated_boards_hit_the_turned_hexes() {
+        for steps in 0..6 {
+            let grid = HexagonGrid::new(5.0, 5.0).rotated(steps);
+            for size in SIZES {
+                for coord in Coord::ALL {
+                    for corner in 0..6 {
+                        let point = towards_corner(&grid, size, coord, corner, 0.95);
+                        assert_eq!(
+                            grid.in_hexagon(point, size),
+                            Some(coord),
+                            "{:?} turned {}",
+                            coord,
+                            steps
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_board_with_no_room_hits_nothing() {
+        let grid = HexagonGrid::new(5.0, 5.0);
+        let size = Size::new(8.0, 8.0);
+        assert_eq!(grid.in_hexagon(Point::new(4.0, 4.0), size), None);
     }
 }
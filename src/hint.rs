@@ -0,0 +1,37 @@
+use std::fmt;
+
+use crate::locale::{tr, tr_args};
+
+// How many hints each player may ask for in a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintSetting {
+    Unlimited,
+    PerGame(u8),
+}
+
+impl HintSetting {
+    pub const ALL: [HintSetting; 4] = [
+        HintSetting::Unlimited,
+        HintSetting::PerGame(3),
+        HintSetting::PerGame(1),
+        HintSetting::PerGame(0),
+    ];
+
+    pub fn limit(&self) -> Option<u8> {
+        match self {
+            HintSetting::Unlimited => None,
+            HintSetting::PerGame(limit) => Some(*limit),
+        }
+    }
+}
+
+impl fmt::Display for HintSetting {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            HintSetting::Unlimited => tr("hints-unlimited"),
+            HintSetting::PerGame(0) => tr("hints-none"),
+            HintSetting::PerGame(limit) => tr_args("hints-each", &[("count", (*limit).into())]),
+        };
+        write!(f, "{}", label)
+    }
+}
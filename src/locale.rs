@@ -0,0 +1,151 @@
+// Translated text for everything the player reads. Each language has a Fluent
+// catalogue under `locales/`, built into the binary; the language picked in
+// the menu applies to the whole program at once. Messages missing from a
+// catalogue fall back to English, then to their id.
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource, FluentValue};
+use sixes_game::PlayerID;
+use unic_langid::LanguageIdentifier;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+// Index into `Language::ALL` of the language in use
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    // Bundles are not `Sync`, so each thread builds its own on first use
+    static BUNDLES: Vec<FluentBundle<FluentResource>> =
+        Language::ALL.iter().map(|language| language.bundle()).collect();
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    // Name used in the settings file, which is also the language tag
+    pub fn key(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Language::ALL
+            .into_iter()
+            .find(|language| language.key() == key)
+    }
+
+    fn catalogue(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("locales/en.ftl"),
+            Language::German => include_str!("locales/de.ftl"),
+        }
+    }
+
+    fn bundle(&self) -> FluentBundle<FluentResource> {
+        let id: LanguageIdentifier = self.key().parse().expect("language keys are valid tags");
+        let resource = FluentResource::try_new(self.catalogue().to_string())
+            .expect("catalogues are valid Fluent");
+        let mut bundle = FluentBundle::new(vec![id]);
+        // Isolation marks around arguments show up as boxes in iced's font
+        bundle.set_use_isolating(false);
+        bundle
+            .add_resource(resource)
+            .expect("catalogues have no duplicate messages");
+        bundle
+    }
+}
+
+// Each language is listed under its own name
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+pub fn set_language(language: Language) {
+    let index = Language::ALL
+        .iter()
+        .position(|l| *l == language)
+        .unwrap_or(0);
+    CURRENT.store(index, Ordering::Relaxed);
+}
+
+pub fn tr(id: &str) -> String {
+    tr_args(id, &[])
+}
+
+// The message `id` with named arguments filled in
+pub fn tr_args(id: &str, args: &[(&str, FluentValue)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    let current = CURRENT.load(Ordering::Relaxed);
+    BUNDLES.with(|bundles| {
+        [current, 0]
+            .into_iter()
+            .map(|index| &bundles[index])
+            .find_map(|bundle| {
+                let pattern = bundle.get_message(id)?.value()?;
+                let mut errors = Vec::new();
+                Some(
+                    bundle
+                        .format_pattern(pattern, Some(&fluent_args), &mut errors)
+                        .into_owned(),
+                )
+            })
+            .unwrap_or_else(|| id.to_string())
+    })
+}
+
+// "Player One" or "Player Two", for use as an argument to other messages
+pub fn player(player: PlayerID) -> String {
+    match player {
+        PlayerID::One => tr("player-one"),
+        PlayerID::Two => tr("player-two"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_ids(language: Language) -> Vec<&'static str> {
+        let mut ids: Vec<&str> = language
+            .catalogue()
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id))
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn catalogues_have_the_same_messages() {
+        let english = message_ids(Language::English);
+        for language in Language::ALL {
+            assert_eq!(message_ids(language), english, "{}", language);
+        }
+    }
+
+    #[test]
+    fn arguments_and_plurals_are_filled_in() {
+        let stones = |count: u8| tr_args("stones", &[("count", count.into())]);
+        assert_eq!(stones(1), "1 stone");
+        assert_eq!(stones(3), "3 stones");
+        assert_eq!(tr("no-such-message"), "no-such-message");
+    }
+}
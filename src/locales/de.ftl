@@ -0,0 +1,220 @@
+# Deutsch. Hex names like C2 and the move notation are the same in every language.
+
+## Shared
+
+player-one = Spieler Eins
+player-two = Spieler Zwei
+stones =
+    { $count ->
+        [one] { $count } Stein
+       *[other] { $count } Steine
+    }
+analyse = Analysieren
+coordinates = Koordinaten
+undo = Rückgängig
+previous = Zurück
+try-again = Noch einmal
+skip = Überspringen
+
+## Menu
+
+rules = Regeln
+rules-objective = Ziel: Halte eine Dreierreihe zwei Züge lang.
+rules-may = In deinem Zug darfst du:
+rules-empty = 1. Einen Stein auf ein leeres Feld setzen. Ist es nicht das Mittelfeld, bekommst du zwei Steine zurück.
+rules-capture = 2. n + 1 Steine auf ein Feld mit n gegnerischen Steinen setzen. Die gegnerischen Steine werden vom Brett genommen.
+rules-king = 3. Einen König auf ein Feld mit beliebig vielen gegnerischen Steinen setzen, außer auf das Mittelfeld.
+rules-may-not = In deinem Zug darfst du nicht:
+rules-last-played = Auf das Feld setzen, auf das im letzten Zug gesetzt wurde.
+tutorial = Einführung
+start = Start
+load = Laden
+resume = Fortsetzen
+puzzles = Rätsel
+moves-placeholder = Züge, z. B. C2 B1 KC1
+animations = Animationen
+join-online = Online spielen
+host = Server
+room = Raum
+create = Erstellen
+join = Beitreten
+watch = Zuschauen
+
+## Settings
+
+clock-untimed = Ohne Uhr
+clock-blitz = 5 Min. Sudden Death
+clock-rapid = 10 Min. + 5 s
+clock-byoyomi = 5 Min. + 3 × 30 s Byoyomi
+hints-unlimited = Beliebig viele Tipps
+hints-none = Keine Tipps
+hints-each =
+    { $count ->
+        [one] { $count } Tipp pro Spieler
+       *[other] { $count } Tipps pro Spieler
+    }
+theme-classic = Klassisch
+theme-dark = Dunkel
+theme-high-contrast = Hoher Kontrast
+theme-colour-blind = Farbenblind-sicher
+
+## Notices and errors
+
+settings-not-saved = Einstellungen konnten nicht gespeichert werden: { $error }
+autosave-failed = Automatisches Speichern fehlgeschlagen: { $error }
+load-failed = Das Spiel konnte nicht geladen werden: { $error }
+no-saved-game = Kein gespeichertes Spiel
+invalid-position = Ungültige Stellung: { $error }
+game-saved = Spiel gespeichert
+save-failed = Speichern fehlgeschlagen: { $error }
+unreadable-move = { $move } ist unlesbar
+illegal-moves = die Züge sind nicht erlaubt
+unknown-tag = unbekannte Angabe { $tag }
+storage-unavailable = der lokale Speicher ist nicht verfügbar
+storage-write-failed = in den lokalen Speicher konnte nicht geschrieben werden
+
+## Game screen
+
+win = { $player } gewinnt!
+win-on-time = { $player } gewinnt auf Zeit!
+win-by-resignation = { $player } gewinnt durch Aufgabe!
+draw-agreed = Remis vereinbart
+menu = Menü
+stop-analysis = Analyse beenden
+save = Speichern
+hint = Tipp
+hints-left = Tipp (noch { $count })
+stones-in-hand = Steine: { $count }
+king = König
+focus = Auswahl { $hex }.
+keyboard-help = Wähle ein Feld mit den Pfeiltasten, eine Figur mit S oder K und setze mit Enter.
+rotate = Drehen
+flip = Wenden
+preview-takes = schlägt { $count }
+preview-stock = +{ $count } Vorrat
+preview-triple = Dreierreihe!
+
+## Analysis and history
+
+analysing = Analysiere...
+evaluation = Tiefe { $depth }: { $eval } für { $player }
+no-moves-to-analyse = Keine Züge zu analysieren
+best-line = Beste Fortsetzung: { $line }
+to-move = { $player } ist am Zug
+game-over = Spiel beendet
+resume-from-here = Von hier weiterspielen
+
+## Online play
+
+connecting = Verbinde...
+out-of-sync = Nicht mehr im Gleichstand mit dem Server
+draw-declined = Remis abgelehnt
+takeback-declined = Zurücknahme abgelehnt
+chat = { $player }: { $text }
+reconnecting = Verbindung verloren, verbinde neu...
+online-unavailable = Online-Spiele gibt es in der Browser-Version nicht
+room-seat = Raum { $room } - du bist { $player }
+room-only = Raum { $room }
+watching = { $count } schauen zu
+resign = Aufgeben
+accept-draw = Remis annehmen
+decline-draw = Remis ablehnen
+draw-offered = Remis angeboten
+offer-draw = Remis anbieten
+accept-takeback = Zurücknahme annehmen
+decline-takeback = Zurücknahme ablehnen
+takeback-requested = Zurücknahme erbeten
+malformed-message = Der Server hat etwas Unlesbares geschickt: { $error }
+server-malformed = Der Server konnte eine Nachricht nicht lesen
+room-exists = Den Raum { $room } gibt es schon
+no-such-room = Es gibt keinen Raum { $room }
+room-full = Der Raum { $room } ist voll
+seat-taken = Der Platz von { $player } ist besetzt
+not-in-room = Du bist in keinem Raum
+not-seated = Das dürfen nur die Spieler
+illegal-move = { $move } ist hier nicht erlaubt
+not-your-turn = Du bist nicht am Zug
+no-draw-offer = Es gibt kein Remisangebot
+nothing-to-take-back = Es gibt nichts zurückzunehmen
+no-takeback-request = Es gibt keine Bitte um Zurücknahme
+
+## Descriptions of the board
+
+hex-king = { $hex }: König von { $player }
+hex-stones = { $hex }: { $stones } von { $player }
+hex-empty = { $hex }: leer
+board-to-move = { $player } ist am Zug.
+board-won = { $player } hat gewonnen.
+board-won-on-time = { $player } hat auf Zeit gewonnen.
+hand-king = { $player } hat { $stones }, König noch in der Hand.
+hand-no-king = { $player } hat { $stones }, König gesetzt.
+board-empty = Das Brett ist leer.
+board-blocked = Auf { $hex } darf in diesem Zug nicht gesetzt werden.
+no-moves-yet = Noch keine Züge.
+last-stones = { $player } hat auf { $hex } gesetzt ({ $stones } auf dem Feld)
+last-king = { $player } hat den König auf { $hex } gesetzt
+last-capturing = dabei { $stones } geschlagen
+last-bonus = und { $stones } zurückbekommen
+last-triple = womit eine Dreierreihe entsteht
+
+## Replays
+
+replay-win = { $player } gewinnt
+replay-win-on-time = { $player } gewinnt auf Zeit
+replay-unfinished = Nicht beendet
+
+## Tutorial
+
+lesson-heading = Lektion { $number } von { $total }: { $title }
+next-lesson = Nächste Lektion
+lesson-complete = Lektion geschafft. Mach mit der nächsten weiter oder versuche es noch einmal.
+lesson-empty-title = Auf leere Felder setzen
+lesson-empty-explanation = Ein Stein auf einem leeren Feld kostet einen Stein aus deinem Vorrat. Überall außer auf dem Mittelfeld C2 bekommst du zwei Steine zurück, du gewinnst also einen. Setze einen Stein auf ein leeres Feld außer C2.
+lesson-empty-wrong = Das Mittelfeld gibt keine Steine zurück. Versuche ein anderes leeres Feld.
+lesson-empty-success = Du hast einen Stein gesetzt und zwei zurückbekommen: 13 Steine statt 12.
+lesson-capture-title = Schlagen mit n + 1 Steinen
+lesson-capture-explanation = Ein Feld mit n gegnerischen Steinen gehört dir, wenn du n + 1 Steine darauf setzt. Die geschlagenen Steine verlassen das Brett. Spieler Zwei hält B1 mit 2 Steinen: Nimm es mit 3.
+lesson-capture-wrong = Sieh dir B1 an, wo Spieler Zwei 2 Steine hat.
+lesson-capture-success = Jetzt halten drei Steine B1. Spieler Zwei kann es mit 4 zurückerobern.
+lesson-king-title = Den König setzen
+lesson-king-explanation = Einmal pro Spiel darfst du deinen König auf einen gegnerischen Stapel setzen, egal wie hoch, nur nicht auf das Mittelfeld. Ein König kann nie geschlagen werden. Setze deinen König auf B1.
+lesson-king-wrong = Wähle den König und setze ihn auf B1.
+lesson-king-success = B1 gehört dir für den Rest des Spiels.
+lesson-last-played-title = Nicht das zuletzt gespielte Feld
+lesson-last-played-explanation = Niemand darf auf das Feld setzen, auf das im Zug davor gesetzt wurde. Spieler Zwei hat gerade B1 genommen, also kannst du es nicht sofort zurückholen. Schlage stattdessen den einzelnen Stein auf C1.
+lesson-last-played-wrong = Schlage den Stein auf C1 mit 2 von deinen.
+lesson-last-played-success = Im nächsten Zug ist B1 wieder frei, wenn Spieler Zwei nicht zuerst dort setzt.
+lesson-triple-title = Mit einer Dreierreihe gewinnen
+lesson-triple-explanation = Drei Felder in einer Reihe sind eine Dreierreihe. Steht deine Dreierreihe nach dem nächsten Zug deines Gegners noch, gewinnst du. Vervollständige die Reihe A1, B1, C1.
+lesson-triple-wrong = Zu deinen Steinen auf A1 und B1 fehlt ein dritter auf C1.
+lesson-triple-success = Spieler Zwei hätte die Reihe mit einem Schlag auf A1 oder B1 brechen können, hat aber D2 gespielt und verloren.
+
+## Why a move is not allowed
+
+illegal-last-played = Auf { $hex } wurde im letzten Zug gesetzt, deshalb darf jetzt niemand dort setzen.
+illegal-king-captured = Ein König kann nie geschlagen werden.
+illegal-own-hex = { $hex } gehört schon dir.
+illegal-supply = { $stones } Steine zu schlagen braucht { $needed } von deinen, du hast { $supply }.
+illegal-king-played = Du hast deinen König schon gesetzt.
+illegal-king-centre = Könige dürfen nicht auf das Mittelfeld gesetzt werden.
+illegal-king-empty = Ein König darf nur auf gegnerische Steine gesetzt werden.
+illegal-other = Dieser Zug ist hier nicht erlaubt.
+
+## Puzzles
+
+puzzle-heading = Rätsel { $number } von { $total }: { $player } ist am Zug. { $goal }
+solved-before = (schon gelöst)
+next-puzzle = Nächstes Rätsel
+puzzles-solved = { $count } von { $total } gelöst
+goal-win = Gewinne in { $turns }
+goal-survive =
+    { $turns ->
+        [one] Überstehe den nächsten Zug
+       *[other] Überstehe { $turns } Züge
+    }
+goal-prevent = Verhindere die Dreierreihe
+puzzle-wrong = Dieser Zug reicht nicht. Sieh dir die Antwort an und versuche es dann noch einmal.
+puzzle-good = Gut. Noch: { $goal }.
+puzzle-solved = Gelöst!
+puzzle-solved-unsaved = Gelöst! Der Fortschritt konnte nicht gespeichert werden: { $error }
+puzzle-failed = Der Gegner ist entkommen. Versuche es noch einmal.
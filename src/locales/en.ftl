@@ -0,0 +1,220 @@
+# English, the reference catalogue. Every other catalogue has the same messages.
+
+## Shared
+
+player-one = Player One
+player-two = Player Two
+stones =
+    { $count ->
+        [one] { $count } stone
+       *[other] { $count } stones
+    }
+analyse = Analyse
+coordinates = Coordinates
+undo = Undo
+previous = Previous
+try-again = Try again
+skip = Skip
+
+## Menu
+
+rules = Rules
+rules-objective = Objective: Maintain a three in a row for two turns straight.
+rules-may = On your turn you may:
+rules-empty = 1. Play a stone on an empty hex. If this hex is not the center hex you get two stones back.
+rules-capture = 2. Play n + 1 stones on an occupied hex with n stones of the opposite colour. The other stones are removed from the board.
+rules-king = 3. Play a king on a hex with any number of stones of the opposite colour except the center hex.
+rules-may-not = On your turn you may not:
+rules-last-played = Play on a hex that has been played on in the last turn.
+tutorial = Tutorial
+start = Start
+load = Load
+resume = Resume
+puzzles = Puzzles
+moves-placeholder = Moves, e.g. C2 B1 KC1
+animations = Animations
+join-online = Join online game
+host = Host
+room = Room
+create = Create
+join = Join
+watch = Watch
+
+## Settings
+
+clock-untimed = No clock
+clock-blitz = 5 min sudden death
+clock-rapid = 10 min + 5 s
+clock-byoyomi = 5 min + 3 x 30 s byoyomi
+hints-unlimited = Unlimited hints
+hints-none = No hints
+hints-each =
+    { $count ->
+        [one] { $count } hint each
+       *[other] { $count } hints each
+    }
+theme-classic = Classic
+theme-dark = Dark
+theme-high-contrast = High contrast
+theme-colour-blind = Colour-blind safe
+
+## Notices and errors
+
+settings-not-saved = Could not save settings: { $error }
+autosave-failed = Autosave failed: { $error }
+load-failed = Could not load the game: { $error }
+no-saved-game = No saved game
+invalid-position = Invalid position: { $error }
+game-saved = Game saved
+save-failed = Save failed: { $error }
+unreadable-move = cannot read { $move }
+illegal-moves = the moves are not legal
+unknown-tag = unknown tag { $tag }
+storage-unavailable = local storage is not available
+storage-write-failed = could not write to local storage
+
+## Game screen
+
+win = { $player } wins!
+win-on-time = { $player } wins on time!
+win-by-resignation = { $player } wins by resignation!
+draw-agreed = Draw agreed
+menu = Menu
+stop-analysis = Stop analysis
+save = Save
+hint = Hint
+hints-left = Hint ({ $count } left)
+stones-in-hand = Stones: { $count }
+king = King
+focus = Focus { $hex }.
+keyboard-help = Use the arrow keys to pick a hex, S or K to choose a piece and Enter to play.
+rotate = Rotate
+flip = Flip
+preview-takes = takes { $count }
+preview-stock = +{ $count } stock
+preview-triple = triple!
+
+## Analysis and history
+
+analysing = Analysing...
+evaluation = Depth { $depth }: { $eval } for { $player }
+no-moves-to-analyse = No moves to analyse
+best-line = Best line: { $line }
+to-move = { $player } to move
+game-over = Game over
+resume-from-here = Resume from here
+
+## Online play
+
+connecting = Connecting...
+out-of-sync = Out of sync with the server
+draw-declined = Draw declined
+takeback-declined = Takeback declined
+chat = { $player }: { $text }
+reconnecting = Connection lost, reconnecting...
+online-unavailable = Online play is not available in the browser build
+room-seat = Room { $room } - you are { $player }
+room-only = Room { $room }
+watching = { $count } watching
+resign = Resign
+accept-draw = Accept draw
+decline-draw = Decline draw
+draw-offered = Draw offered
+offer-draw = Offer draw
+accept-takeback = Accept takeback
+decline-takeback = Decline takeback
+takeback-requested = Takeback requested
+malformed-message = The server sent something unreadable: { $error }
+server-malformed = The server could not read a message
+room-exists = Room { $room } already exists
+no-such-room = There is no room { $room }
+room-full = Room { $room } is full
+seat-taken = The seat of { $player } is taken
+not-in-room = You are not in a room
+not-seated = Only the players can do that
+illegal-move = { $move } is not allowed here
+not-your-turn = It is not your turn
+no-draw-offer = There is no draw offer
+nothing-to-take-back = There is nothing to take back
+no-takeback-request = There is no takeback request
+
+## Descriptions of the board
+
+hex-king = { $hex }: king of { $player }
+hex-stones = { $hex }: { $stones } of { $player }
+hex-empty = { $hex }: empty
+board-to-move = { $player } to move.
+board-won = { $player } has won.
+board-won-on-time = { $player } has won on time.
+hand-king = { $player } has { $stones }, king in hand.
+hand-no-king = { $player } has { $stones }, king played.
+board-empty = The board is empty.
+board-blocked = { $hex } cannot be played this turn.
+no-moves-yet = No moves yet.
+last-stones = { $player } played on { $hex }, which now holds { $stones }
+last-king = { $player } played their king on { $hex }
+last-capturing = capturing { $stones }
+last-bonus = and took { $stones } back
+last-triple = making three in a row
+
+## Replays
+
+replay-win = { $player } wins
+replay-win-on-time = { $player } wins on time
+replay-unfinished = Unfinished
+
+## Tutorial
+
+lesson-heading = Lesson { $number } of { $total }: { $title }
+next-lesson = Next lesson
+lesson-complete = Lesson complete. Go on to the next one or try it again.
+lesson-empty-title = Playing on empty hexes
+lesson-empty-explanation = A stone on an empty hex costs one stone from your supply. Anywhere but the centre hex C2 you get two stones back, so you gain one. Play a stone on an empty hex other than C2.
+lesson-empty-wrong = The centre hex gives no stones back. Try another empty hex.
+lesson-empty-success = You played one stone and took two back: 13 stones instead of 12.
+lesson-capture-title = Capturing with n + 1 stones
+lesson-capture-explanation = A hex with n of your opponent's stones is yours if you play n + 1 stones on it. The captured stones leave the board. Player Two holds B1 with 2 stones: take it with 3.
+lesson-capture-wrong = Look at B1, where Player Two has 2 stones.
+lesson-capture-success = Three stones now hold B1. Player Two can retake it with 4.
+lesson-king-title = Playing your king
+lesson-king-explanation = Once a game you may play your king on any of your opponent's stacks, however tall, except on the centre hex. A king can never be captured. Play your king on B1.
+lesson-king-wrong = Pick the king and play it on B1.
+lesson-king-success = B1 is yours for the rest of the game.
+lesson-last-played-title = Not the last-played hex
+lesson-last-played-explanation = Nobody may play on the hex that was played on the turn before. Player Two has just taken B1, so you cannot take it straight back. Capture the single stone on C1 instead.
+lesson-last-played-wrong = Capture the stone on C1 with 2 of yours.
+lesson-last-played-success = Next turn B1 is open again, unless Player Two plays there first.
+lesson-triple-title = Winning with a triple
+lesson-triple-explanation = Three hexes in a row are a triple. If your triple is still on the board after your opponent's next move, you win. Complete the row A1, B1, C1.
+lesson-triple-wrong = Your stones on A1 and B1 need a third on C1.
+lesson-triple-success = Player Two had one move to break the row by capturing A1 or B1, played D2 instead and lost.
+
+## Why a move is not allowed
+
+illegal-last-played = { $hex } was played on last turn, so nobody may play there now.
+illegal-king-captured = A king can never be captured.
+illegal-own-hex = { $hex } is already yours.
+illegal-supply = Taking { $stones } stones needs { $needed } of yours and you have { $supply }.
+illegal-king-played = You have already played your king.
+illegal-king-centre = Kings may not be played on the centre hex.
+illegal-king-empty = A king can only be played on your opponent's stones.
+illegal-other = That move is not allowed here.
+
+## Puzzles
+
+puzzle-heading = Puzzle { $number } of { $total }: { $player } to move. { $goal }
+solved-before = (solved before)
+next-puzzle = Next puzzle
+puzzles-solved = Solved { $count } of { $total }
+goal-win = Win in { $turns }
+goal-survive =
+    { $turns ->
+        [one] Survive the next turn
+       *[other] Survive { $turns } turns
+    }
+goal-prevent = Stop the triple
+puzzle-wrong = That move falls short. Watch the reply, then try again.
+puzzle-good = Good. { $goal } to go.
+puzzle-solved = Solved!
+puzzle-solved-unsaved = Solved! Could not save progress: { $error }
+puzzle-failed = The opponent got away. Try again.
@@ -1,23 +1,71 @@
+use iced::time::{self, Duration, Instant};
 use iced::widget::canvas::event::{self, Event};
-use iced::widget::canvas::{Canvas, Cursor, Frame, Geometry, Program, Stroke};
-use iced::widget::{button, column, row, text};
-use iced::{mouse, Padding};
-use iced::{theme, Alignment, Application, Color, Command, Element, Length};
+use iced::widget::canvas::{self, Canvas, Cursor, Frame, Geometry, LineDash, Program, Stroke};
+use iced::widget::{button, checkbox, column, pick_list, row, scrollable, text, text_input};
+use iced::{alignment, keyboard, mouse, subscription, touch, Padding, Point};
+use iced::{theme, Alignment, Application, Command, Element, Length, Subscription};
 
-use sixes_game;
+mod analysis;
+mod animation;
+mod clock;
+mod describe;
 mod hexagon;
+mod hint;
+mod locale;
+mod network;
+mod palette;
 mod pieces;
 mod player;
+mod puzzles;
+#[cfg(not(target_arch = "wasm32"))]
+mod render;
+#[cfg(not(target_arch = "wasm32"))]
+mod replay;
+mod settings;
+mod storage;
+mod tutorial;
+use animation::Animation;
+use clock::{clock_text, ClockSetting};
 use hexagon::HexagonGrid;
+use hint::HintSetting;
+use locale::{player as name, tr, tr_args, Language};
+use network::Connection;
+use palette::{Palette, ThemeSetting};
 use player::Player;
+use puzzles::Attempt;
+use settings::Settings;
+use sixes_game::agent::Agent;
+use sixes_game::engine::DEFAULT_DEPTH;
+use sixes_game::notation::{move_to_string, parse_moves, ParseMoveError};
+use sixes_game::record::{from_record, to_record, RecordError};
 use sixes_game::Coord;
 use sixes_game::PlayerID;
-use sixes_game::{GameError, GameState, Play, Sixes};
+use sixes_game::{GameError, GameState, Play, Preview, Sixes};
+use sixes_protocol::{ClientMessage, Outcome, ServerError, ServerMessage, Snapshot, DEFAULT_ADDR};
+use tutorial::{Tutorial, LESSONS};
 
 pub fn main() -> iced::Result {
+    locale::set_language(Settings::load().language);
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let result = match args.first().map(String::as_str) {
+            Some("render") => Some(render::command(&args[1..])),
+            Some("replay") => Some(replay::command(&args[1..])),
+            _ => None,
+        };
+        if let Some(result) = result {
+            if let Err(message) = result {
+                eprintln!("{}", message);
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+    }
     SixesUI::run(iced::Settings {
         window: iced::window::Settings {
             size: (1000, 800),
+            min_size: Some((640, 520)),
             ..iced::window::Settings::default()
         },
         antialiasing: true,
@@ -27,8 +75,21 @@ pub fn main() -> iced::Result {
 
 #[derive(Debug, Clone)]
 enum SixesUI {
-    Menu,
-    Running(State),
+    Menu(MenuState),
+    Running(Box<State>),
+}
+
+#[derive(Debug, Clone)]
+struct MenuState {
+    host: String,
+    room: String,
+    clock: ClockSetting,
+    hints: HintSetting,
+    settings: Settings,
+    can_load: bool,
+    can_resume: bool,
+    notice: String,
+    position: String,
 }
 
 #[derive(Debug, Clone)]
@@ -40,6 +101,48 @@ struct State {
     stone_selected: bool,
     king_selected: bool,
     over_hex: Option<Coord>,
+    online: Option<Online>,
+    // Number of moves into the game being shown; None follows the live position
+    viewing: Option<usize>,
+    // Time of the previous clock tick; the clock is paused from a move until EndTurn
+    last_tick: Option<Instant>,
+    clock_paused: bool,
+    notice: String,
+    // Engine analysis of the shown position runs in the background while on
+    analysing: bool,
+    report: Option<analysis::Report>,
+    hint_setting: HintSetting,
+    hints_used: [u8; 2],
+    // Hex suggested by the last hint, until the move is made
+    hint: Option<Coord>,
+    animations: bool,
+    animation: Option<Animation>,
+    // Hex picked with the keyboard, drawn with a focus ring
+    focus: Option<Coord>,
+    palette: Palette,
+    // Board orientation: clockwise turns in 60 degree steps, and whether
+    // Player Two sits at the bottom instead of Player One
+    rotation: u8,
+    flipped: bool,
+    coordinates: bool,
+    // Set while working through the tutorial lessons
+    tutorial: Option<Tutorial>,
+    // Set while playing one of the built-in puzzles
+    puzzle: Option<Attempt>,
+}
+
+// A game hosted on a sixes-server. The server's `Sixes` is authoritative: moves
+// are sent to it and the local game is only ever rebuilt from its state pushes.
+#[derive(Debug, Clone)]
+struct Online {
+    target: network::Target,
+    connection: Option<Connection>,
+    seat: Option<PlayerID>,
+    outcome: Option<Outcome>,
+    draw_offered_by: Option<PlayerID>,
+    takeback_requested_by: Option<PlayerID>,
+    spectators: usize,
+    status: String,
 }
 
 #[derive(Debug, Clone)]
@@ -50,8 +153,470 @@ pub enum Message {
     SelectKing,
     SelectHex,
     OverHex(Option<Coord>),
+    TapHex(Coord),
     EndTurn,
     EndGame,
+    HostChanged(String),
+    RoomChanged(String),
+    CreateOnline,
+    JoinOnline,
+    WatchOnline,
+    ViewMove(Option<usize>),
+    Branch,
+    ClockSelected(ClockSetting),
+    HintsSelected(HintSetting),
+    Hint,
+    Save,
+    Load,
+    Resume,
+    PositionChanged(String),
+    Analyse,
+    Analysis(analysis::Report),
+    AnimationsToggled(bool),
+    ThemeSelected(ThemeSetting),
+    CoordinatesToggled(bool),
+    LanguageSelected(Language),
+    Rotate,
+    Flip,
+    Tutorial,
+    Lesson(usize),
+    Puzzles,
+    Puzzle(usize),
+    Tick(Instant),
+    Animate(Instant),
+    Key(keyboard::KeyCode),
+    Network(network::Event),
+    Resign,
+    OfferDraw,
+    AcceptDraw,
+    DeclineDraw,
+    Undo,
+    AcceptTakeback,
+    DeclineTakeback,
+}
+
+impl Default for MenuState {
+    fn default() -> Self {
+        MenuState {
+            host: DEFAULT_ADDR.to_string(),
+            room: String::new(),
+            clock: ClockSetting::Untimed,
+            hints: HintSetting::Unlimited,
+            settings: Settings::load(),
+            can_load: storage::read(storage::SAVE_SLOT).is_some(),
+            // Only offer to resume a game that was still being played
+            can_resume: storage::read(storage::AUTOSAVE_SLOT)
+                .and_then(|record| from_record(&record).ok())
+                .is_some_and(|game| game.game_state() == GameState::Running),
+            notice: String::new(),
+            position: String::new(),
+        }
+    }
+}
+
+impl MenuState {
+    fn save_settings(&mut self) {
+        if let Err(message) = self.settings.save() {
+            self.notice = tr_args("settings-not-saved", &[("error", message.into())]);
+        }
+    }
+
+    // A game started from the menu, with the menu's choices applied
+    fn running(&self, game: Sixes, online: Option<Online>) -> SixesUI {
+        let mut state = State::new(game, online);
+        state.hint_setting = self.hints;
+        state.animations = self.settings.animations;
+        state.set_palette(self.settings.theme.palette());
+        state.coordinates = self.settings.coordinates;
+        SixesUI::Running(Box::new(state))
+    }
+}
+
+impl State {
+    fn new(game: Sixes, online: Option<Online>) -> Self {
+        let players = game.players();
+        State {
+            game,
+            player_one: Player::new(players[0], Palette::default().player_one),
+            player_two: Player::new(players[1], Palette::default().player_two),
+            hexes_selectable: Vec::new(),
+            stone_selected: false,
+            king_selected: false,
+            over_hex: None,
+            online,
+            viewing: None,
+            last_tick: None,
+            clock_paused: false,
+            notice: String::new(),
+            analysing: false,
+            report: None,
+            hint_setting: HintSetting::Unlimited,
+            hints_used: [0; 2],
+            hint: None,
+            animations: true,
+            animation: None,
+            focus: None,
+            palette: Palette::default(),
+            rotation: 0,
+            flipped: false,
+            coordinates: false,
+            tutorial: None,
+            puzzle: None,
+        }
+    }
+
+    // Keeps the autosave slot in step with local games so a closed or crashed
+    // session can be resumed from the menu
+    fn autosave(&mut self) {
+        if self.online.is_none() && !self.is_exercise() {
+            if let Err(message) = storage::write(storage::AUTOSAVE_SLOT, &to_record(&self.game)) {
+                self.notice = tr_args("autosave-failed", &[("error", message.into())]);
+            }
+        }
+    }
+
+    // Charges the time since the last tick to the side to move
+    fn run_clock(&mut self, now: Instant) {
+        if let Some(last) = self.last_tick {
+            self.game.tick(now.saturating_duration_since(last));
+        }
+        self.last_tick = Some(now);
+        if self.game.game_state() != GameState::Running {
+            self.clear_selection();
+        }
+    }
+
+    // Lessons and puzzles are set positions that are not saved and cannot be
+    // taken back or branched
+    fn is_exercise(&self) -> bool {
+        self.tutorial.is_some() || self.puzzle.is_some()
+    }
+
+    // Spectators never get to move, whichever side is to play
+    fn is_spectator(&self) -> bool {
+        matches!(&self.online, Some(online) if online.seat.is_none())
+    }
+
+    // Input is only accepted on the live position and never from spectators
+    fn accepts_input(&self) -> bool {
+        self.viewing.is_none() && !self.is_spectator()
+    }
+
+    // The position on screen: the live game or an earlier point in its history
+    fn shown_game(&self) -> Sixes {
+        match self.viewing {
+            Some(ply) => {
+                Sixes::from_moves(&self.game.history()[..ply]).expect("history replays cleanly")
+            }
+            None => self.game.clone(),
+        }
+    }
+
+    // Continues play from the position being viewed, dropping the later moves.
+    // The clocks keep their current times.
+    fn branch(&mut self) {
+        if self.viewing.is_none() {
+            return;
+        }
+        let mut game = self.shown_game();
+        game.set_clock(self.game.clock().cloned());
+        self.game = game;
+        self.viewing = None;
+        self.last_tick = None;
+        self.animation = None;
+        self.clear_selection();
+        let players = self.game.players();
+        self.player_one.update(players[0]);
+        self.player_two.update(players[1]);
+        self.autosave();
+    }
+
+    // Sets up a tutorial lesson's position from scratch
+    fn start_lesson(&mut self, lesson: usize) {
+        let tutorial = Tutorial::new(lesson);
+        self.game = tutorial.lesson().position();
+        self.tutorial = Some(tutorial);
+        self.viewing = None;
+        self.animation = None;
+        self.focus = None;
+        self.clear_selection();
+        let players = self.game.players();
+        self.player_one.update(players[0]);
+        self.player_two.update(players[1]);
+    }
+
+    // Sets up a puzzle's position with the side to move at the bottom
+    fn start_puzzle(&mut self, index: usize) {
+        let attempt = Attempt::new(index);
+        self.game = attempt.position();
+        self.set_bottom_player(attempt.player());
+        self.puzzle = Some(attempt);
+        self.viewing = None;
+        self.animation = None;
+        self.focus = None;
+        self.clear_selection();
+        let players = self.game.players();
+        self.player_one.update(players[0]);
+        self.player_two.update(players[1]);
+    }
+
+    // A flipped board is turned half way round on top of any rotation
+    fn grid(&self) -> HexagonGrid {
+        let flip = if self.flipped { 3 } else { 0 };
+        HexagonGrid::new(5.0, 5.0).rotated(self.rotation + flip)
+    }
+
+    // Player whose controls are drawn above the board
+    fn top_player(&self) -> PlayerID {
+        self.bottom_player().other()
+    }
+
+    // Player whose controls are drawn below the board, nearest the person playing
+    fn bottom_player(&self) -> PlayerID {
+        if self.flipped {
+            PlayerID::Two
+        } else {
+            PlayerID::One
+        }
+    }
+
+    fn set_bottom_player(&mut self, player: PlayerID) {
+        self.flipped = player == PlayerID::Two;
+    }
+
+    fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+        self.player_one.set_color(palette.player_one);
+        self.player_two.set_color(palette.player_two);
+    }
+
+    // Animates the move just made, when animations are on
+    fn start_animation(&mut self) {
+        self.animation = if self.animations {
+            Animation::last_move(&self.game, Instant::now())
+        } else {
+            None
+        };
+    }
+
+    // Moves the running animation on, following a winning move with the
+    // celebration
+    fn animate(&mut self, now: Instant) {
+        let Some(animation) = &mut self.animation else {
+            return;
+        };
+        if !animation.tick(now) {
+            self.animation = if animation.is_move() {
+                Animation::win(&self.game, now)
+            } else {
+                None
+            };
+        }
+    }
+
+    // Drops the chosen piece, its target hexes and any hint
+    fn clear_selection(&mut self) {
+        self.hexes_selectable = Vec::new();
+        self.stone_selected = false;
+        self.king_selected = false;
+        self.hint = None;
+    }
+
+    // Hexes the side to move can play `play` on
+    fn selectable(&self, play: Play) -> Vec<Coord> {
+        self.game
+            .possible_moves()
+            .iter()
+            .filter(|(_, possible)| *possible == play)
+            .map(|(coord, _)| *coord)
+            .collect()
+    }
+
+    fn hints_left(&self, player: PlayerID) -> Option<u8> {
+        let used = self.hints_used[player as usize];
+        self.hint_setting
+            .limit()
+            .map(|limit| limit.saturating_sub(used))
+    }
+
+    // Asks the engine for a move and selects the piece it plays, leaving the
+    // suggested hex highlighted
+    fn show_hint(&mut self) {
+        let player = self.game.turn();
+        if !self.can_play(player) || self.hints_left(player) == Some(0) {
+            return;
+        }
+        // Seeded by the ply so book moves vary over a game but repeat for a position
+        let seed = self.game.history().len() as u64;
+        let Some((coord, play)) = Agent::from_config(DEFAULT_DEPTH, seed).choose_move(&self.game)
+        else {
+            return;
+        };
+        self.hints_used[player as usize] += 1;
+        self.hexes_selectable = self.selectable(play);
+        self.stone_selected = play == Play::Stones;
+        self.king_selected = play == Play::King;
+        self.hint = Some(coord);
+    }
+
+    // Takes back the last move of a local game
+    fn undo(&mut self) {
+        if self.game.undo().is_none() {
+            return;
+        }
+        self.animation = None;
+        self.viewing = None;
+        self.last_tick = None;
+        self.clear_selection();
+        let players = self.game.players();
+        self.player_one.update(players[0]);
+        self.player_two.update(players[1]);
+        self.autosave();
+    }
+
+    // The engine report for the shown position, once the search has reached it
+    fn report(&self) -> Option<&analysis::Report> {
+        let moves = self.shown_game().history();
+        self.report
+            .as_ref()
+            .filter(|report| self.analysing && report.moves == moves)
+    }
+
+    // Keyboard play: arrows and Q/E/A/D/Z/C move the focus between hexes,
+    // S and K pick up a stone or the king, Enter plays and U undoes
+    fn key_message(&mut self, key_code: keyboard::KeyCode) -> Option<Message> {
+        use keyboard::KeyCode;
+
+        let degrees = match key_code {
+            KeyCode::Right | KeyCode::D => Some(0.0),
+            KeyCode::C => Some(60.0),
+            KeyCode::Down => Some(90.0),
+            KeyCode::Z => Some(120.0),
+            KeyCode::Left | KeyCode::A => Some(180.0),
+            KeyCode::Q => Some(240.0),
+            KeyCode::Up => Some(270.0),
+            KeyCode::E => Some(300.0),
+            _ => None,
+        };
+        if let Some(degrees) = degrees {
+            let focus = match self.focus {
+                Some(from) => self.grid().step(from, degrees).unwrap_or(from),
+                None => sixes_game::CENTER_HEX,
+            };
+            self.focus = Some(focus);
+            return Some(Message::OverHex(Some(focus)));
+        }
+
+        let player = self.game.turn();
+        let (stones, king) = match player {
+            PlayerID::One => (self.player_one.stones(), self.player_one.king()),
+            PlayerID::Two => (self.player_two.stones(), self.player_two.king()),
+        };
+        match key_code {
+            KeyCode::S if self.can_play(player) && stones > 0 => Some(Message::SelectStone),
+            KeyCode::K if self.can_play(player) && king => Some(Message::SelectKing),
+            KeyCode::Enter | KeyCode::NumpadEnter | KeyCode::Space if self.focus.is_some() => {
+                self.over_hex = self.focus;
+                Some(Message::SelectHex)
+            }
+            KeyCode::U if !self.is_spectator() && !self.is_exercise() => Some(Message::Undo),
+            KeyCode::H if !self.is_exercise() => Some(Message::Hint),
+            _ => None,
+        }
+    }
+
+    // The outcome of playing the selected piece on the hovered hex
+    fn preview(&self) -> Option<(Coord, Preview)> {
+        let coord = self.over_hex?;
+        let play = if self.stone_selected {
+            Play::Stones
+        } else if self.king_selected {
+            Play::King
+        } else {
+            return None;
+        };
+        if !self.accepts_input() || !self.hexes_selectable.contains(&coord) {
+            return None;
+        }
+        self.game
+            .dry_run(coord, play)
+            .ok()
+            .map(|preview| (coord, preview))
+    }
+
+    // Whether the local user may move for `player` right now
+    fn can_play(&self, player: PlayerID) -> bool {
+        if self.viewing.is_some() {
+            return false;
+        }
+        let seated = match &self.online {
+            Some(online) => online.seat == Some(player) && online.outcome.is_none(),
+            None => true,
+        };
+        seated && self.game.turn() == player && self.game.game_state() == GameState::Running
+    }
+
+    // Rebuilds the game from the server's move list and checks that the local
+    // rules agree with the server on the last captured hex and the result
+    fn apply_snapshot(&mut self, snapshot: Snapshot) -> Result<(), String> {
+        let moves = parse_moves(&snapshot.moves.join(" ")).map_err(notation_error)?;
+        let game = Sixes::from_moves(&moves).map_err(|_| tr("illegal-moves"))?;
+        if game.last_captured() != snapshot.last_captured
+            || game.game_state() != snapshot.game_state
+            || game.turn() != snapshot.turn
+        {
+            return Err(tr("out-of-sync"));
+        }
+
+        let new_move = game.history().len() == self.game.history().len() + 1;
+        self.game = game;
+        let players = self.game.players();
+        self.player_one.update(players[0]);
+        self.player_two.update(players[1]);
+        self.clear_selection();
+        if new_move {
+            self.start_animation();
+        } else {
+            self.animation = None;
+        }
+        // Keep a scrubbed-back view in place, the newest moves just extend the list
+        if let Some(ply) = self.viewing {
+            if ply >= self.game.history().len() {
+                self.viewing = None;
+            }
+        }
+        if let Some(online) = &mut self.online {
+            online.outcome = snapshot.outcome;
+            online.spectators = snapshot.spectators;
+            // Any move or takeback settles an open takeback request
+            online.takeback_requested_by = None;
+            if online.outcome.is_some() {
+                online.draw_offered_by = None;
+            }
+        }
+        Ok(())
+    }
+
+    fn send(&self, message: ClientMessage) {
+        if let Some(connection) = self.online.as_ref().and_then(|o| o.connection.as_ref()) {
+            connection.send(message);
+        }
+    }
+}
+
+impl Online {
+    fn new(host: String, room: String, mode: network::JoinMode) -> Self {
+        Online {
+            target: network::Target { host, room, mode },
+            connection: None,
+            seat: None,
+            outcome: None,
+            draw_offered_by: None,
+            takeback_requested_by: None,
+            spectators: 0,
+            status: tr("connecting"),
+        }
+    }
 }
 
 impl Application for SixesUI {
@@ -61,7 +626,19 @@ impl Application for SixesUI {
     type Theme = theme::Theme;
 
     fn new(_flags: ()) -> (Self, Command<Self::Message>) {
-        (SixesUI::Menu, Command::none())
+        (SixesUI::Menu(MenuState::default()), Command::none())
+    }
+
+    fn theme(&self) -> theme::Theme {
+        let dark = match self {
+            SixesUI::Menu(menu) => menu.settings.theme.palette().dark,
+            SixesUI::Running(state) => state.palette.dark,
+        };
+        if dark {
+            theme::Theme::Dark
+        } else {
+            theme::Theme::Light
+        }
     }
 
     fn title(&self) -> String {
@@ -70,38 +647,126 @@ impl Application for SixesUI {
 
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
         match self {
-            SixesUI::Menu => match message {
+            SixesUI::Menu(menu) => match message {
                 Message::Start => {
-                    let game = Sixes::start();
-                    let players = game.players();
-                    *self = SixesUI::Running(State {
-                        game,
-                        player_one: Player::new(players[0]),
-                        player_two: Player::new(players[1]),
-                        hexes_selectable: Vec::new(),
-                        stone_selected: false,
-                        king_selected: false,
-                        over_hex: None,
-                    });
+                    let game = match menu.clock.control() {
+                        Some(control) => Sixes::start_with_clock(control),
+                        None => Sixes::start(),
+                    };
+                    *self = menu.running(game, None);
+                }
+                Message::ClockSelected(setting) => menu.clock = setting,
+                Message::HintsSelected(setting) => menu.hints = setting,
+                Message::AnimationsToggled(animations) => {
+                    menu.settings.animations = animations;
+                    menu.save_settings();
+                }
+                Message::ThemeSelected(theme) => {
+                    menu.settings.theme = theme;
+                    menu.save_settings();
+                }
+                Message::CoordinatesToggled(coordinates) => {
+                    menu.settings.coordinates = coordinates;
+                    menu.save_settings();
+                }
+                Message::LanguageSelected(language) => {
+                    locale::set_language(language);
+                    menu.settings.language = language;
+                    menu.save_settings();
+                }
+                Message::Tutorial => {
+                    let mut running = menu.running(Sixes::start(), None);
+                    if let SixesUI::Running(state) = &mut running {
+                        state.start_lesson(0);
+                    }
+                    *self = running;
+                }
+                Message::Puzzles => {
+                    let solved = puzzles::solved();
+                    let first = puzzles::all()
+                        .iter()
+                        .position(|puzzle| !solved.contains(&puzzle.to_line()))
+                        .unwrap_or(0);
+                    let mut running = menu.running(Sixes::start(), None);
+                    if let SixesUI::Running(state) = &mut running {
+                        state.start_puzzle(first);
+                    }
+                    *self = running;
+                }
+                Message::Load | Message::Resume => {
+                    let slot = match message {
+                        Message::Load => storage::SAVE_SLOT,
+                        _ => storage::AUTOSAVE_SLOT,
+                    };
+                    match storage::read(slot).map(|record| from_record(&record)) {
+                        Some(Ok(game)) => *self = menu.running(game, None),
+                        Some(Err(e)) => {
+                            menu.notice =
+                                tr_args("load-failed", &[("error", record_error(e).into())])
+                        }
+                        None => menu.notice = tr("no-saved-game"),
+                    }
+                }
+                Message::PositionChanged(position) => menu.position = position,
+                // An analysis board starts from the typed moves, or the start if none
+                Message::Analyse => match parse_moves(&menu.position)
+                    .map_err(notation_error)
+                    .and_then(|moves| Sixes::from_moves(&moves).map_err(|_| tr("illegal-moves")))
+                {
+                    Ok(game) => {
+                        *self = menu.running(game, None);
+                        if let SixesUI::Running(state) = self {
+                            state.analysing = true;
+                        }
+                    }
+                    Err(e) => menu.notice = tr_args("invalid-position", &[("error", e.into())]),
+                },
+                Message::HostChanged(host) => menu.host = host,
+                Message::RoomChanged(room) => menu.room = room,
+                Message::CreateOnline | Message::JoinOnline | Message::WatchOnline
+                    if !menu.room.trim().is_empty() =>
+                {
+                    let mode = match message {
+                        Message::CreateOnline => network::JoinMode::Create,
+                        Message::JoinOnline => network::JoinMode::Join,
+                        _ => network::JoinMode::Watch,
+                    };
+                    let online = Online::new(
+                        menu.host.trim().to_string(),
+                        menu.room.trim().to_string(),
+                        mode,
+                    );
+                    *self = menu.running(Sixes::start(), Some(online));
                 }
                 _ => {}
             },
             SixesUI::Running(state) => match message {
+                Message::SelectStone | Message::SelectKing | Message::SelectHex
+                    if !state.accepts_input() => {}
+                Message::ViewMove(ply) => {
+                    let moves = state.game.history().len();
+                    state.viewing = ply.filter(|ply| *ply < moves);
+                    state.clear_selection();
+                }
+                Message::Branch if state.online.is_none() && !state.is_exercise() => state.branch(),
+                Message::Hint if state.online.is_none() => state.show_hint(),
+                // Engine help is kept out of online games
+                Message::Analyse if state.online.is_none() => {
+                    state.analysing = !state.analysing;
+                    state.report = None;
+                }
+                // Reports for a position that is no longer shown are stale
+                Message::Analysis(report)
+                    if state.analysing && report.moves == state.shown_game().history() =>
+                {
+                    state.report = Some(report);
+                }
                 Message::SelectStone => {
                     if !state.stone_selected {
                         println!("Selecting stone");
                         state.stone_selected = true;
                         state.king_selected = false;
-                        state.hexes_selectable = state
-                            .game
-                            .possible_moves()
-                            .iter()
-                            .filter(|(_, play)| match play {
-                                Play::Stones => true,
-                                _ => false,
-                            })
-                            .map(|(coord, _)| coord.clone())
-                            .collect();
+                        state.hexes_selectable = state.selectable(Play::Stones);
                     } else {
                         println!("Deselecting stone");
                         state.stone_selected = false;
@@ -113,16 +778,7 @@ impl Application for SixesUI {
                         println!("Selecting king");
                         state.king_selected = true;
                         state.stone_selected = false;
-                        state.hexes_selectable = state
-                            .game
-                            .possible_moves()
-                            .iter()
-                            .filter(|(_, play)| match play {
-                                Play::King => true,
-                                _ => false,
-                            })
-                            .map(|(coord, _)| coord.clone())
-                            .collect();
+                        state.hexes_selectable = state.selectable(Play::King);
                     } else {
                         println!("Deselecting king");
                         state.king_selected = false;
@@ -135,6 +791,50 @@ impl Application for SixesUI {
                     }
                     let coord = state.over_hex.unwrap();
                     println!("Selecting hex {:?}", coord);
+                    if state.online.is_some() {
+                        let play = if state.stone_selected {
+                            Play::Stones
+                        } else if state.king_selected {
+                            Play::King
+                        } else {
+                            return Command::none();
+                        };
+                        if state.hexes_selectable.contains(&coord) {
+                            state.send(ClientMessage::Move {
+                                notation: move_to_string(coord, play),
+                            });
+                        }
+                        return Command::none();
+                    }
+                    // Lessons turn down moves off the point with a reason
+                    if let Some(tutorial) = &mut state.tutorial {
+                        let play = if state.king_selected {
+                            Play::King
+                        } else {
+                            Play::Stones
+                        };
+                        if (state.stone_selected || state.king_selected)
+                            && !tutorial.attempt(&state.game, coord, play)
+                        {
+                            return Command::none();
+                        }
+                    }
+                    // Puzzles play wrong moves too, so the reply shows the refutation
+                    if let Some(attempt) = &mut state.puzzle {
+                        let play = if state.king_selected {
+                            Play::King
+                        } else {
+                            Play::Stones
+                        };
+                        if (state.stone_selected || state.king_selected)
+                            && !attempt.attempt(&state.game, coord, play)
+                        {
+                            return Command::none();
+                        }
+                    }
+                    if !state.clock_paused {
+                        state.run_clock(Instant::now());
+                    }
                     let res = if state.stone_selected {
                         state.game.play(coord, Play::Stones)
                     } else if state.king_selected {
@@ -143,27 +843,150 @@ impl Application for SixesUI {
                         Err(GameError::InvalidPlay)
                     };
 
-                    match res {
-                        Ok(_) => {
-                            return Command::perform(async {}, move |_| Message::EndTurn);
-                        }
-                        Err(_) => {}
+                    if res.is_ok() {
+                        state.clock_paused = true;
+                        return Command::perform(async {}, move |_| Message::EndTurn);
                     }
                 }
                 Message::OverHex(coord) => {
                     state.over_hex = coord;
                 }
+                Message::TapHex(coord) => {
+                    state.over_hex = Some(coord);
+                    return self.update(Message::SelectHex);
+                }
+                Message::Tick(now) if !state.clock_paused => state.run_clock(now),
+                Message::Animate(now) => state.animate(now),
+                Message::Key(key_code) => {
+                    if let Some(message) = state.key_message(key_code) {
+                        return self.update(message);
+                    }
+                }
                 Message::EndTurn => {
                     println!("ending turn");
-                    state.hexes_selectable = Vec::new();
-                    state.stone_selected = false;
-                    state.king_selected = false;
+                    state.clock_paused = false;
+                    state.last_tick = None;
+                    state.clear_selection();
+                    if let Some((coord, play)) =
+                        state.tutorial.as_mut().and_then(Tutorial::take_reply)
+                    {
+                        let _ = state.game.play(coord, play);
+                    }
+                    if let Some(attempt) = &mut state.puzzle {
+                        if state.game.turn() != attempt.player() {
+                            if let Some((coord, play)) = attempt.reply(&state.game) {
+                                let _ = state.game.play(coord, play);
+                            }
+                            attempt.after_reply(&state.game);
+                        }
+                    }
                     let players = state.game.players();
                     state.player_one.update(players[0]);
                     state.player_two.update(players[1]);
+                    state.notice = String::new();
+                    state.autosave();
+                    state.start_animation();
                 }
                 Message::Menu => {
-                    *self = SixesUI::Menu;
+                    state.autosave();
+                    *self = SixesUI::Menu(MenuState::default());
+                }
+                Message::Save => {
+                    state.notice = match storage::write(storage::SAVE_SLOT, &to_record(&state.game))
+                    {
+                        Ok(()) => tr("game-saved"),
+                        Err(message) => tr_args("save-failed", &[("error", message.into())]),
+                    };
+                }
+                Message::Network(event) => {
+                    let Some(online) = &mut state.online else {
+                        return Command::none();
+                    };
+                    match event {
+                        network::Event::Connected(connection) => {
+                            online.connection = Some(connection);
+                        }
+                        network::Event::Joined(seat) => {
+                            online.seat = seat;
+                            online.status = String::new();
+                            // Seat the local player at the bottom of the board;
+                            // spectators see Player One there
+                            state.set_bottom_player(seat.unwrap_or(PlayerID::One));
+                        }
+                        network::Event::State(snapshot) => {
+                            if let Err(message) = state.apply_snapshot(snapshot) {
+                                if let Some(online) = &mut state.online {
+                                    online.status = message;
+                                }
+                            }
+                        }
+                        network::Event::Server(ServerMessage::DrawOffered { by }) => {
+                            online.draw_offered_by = Some(by);
+                        }
+                        network::Event::Server(ServerMessage::DrawDeclined { .. }) => {
+                            online.draw_offered_by = None;
+                            online.status = tr("draw-declined");
+                        }
+                        network::Event::Server(ServerMessage::TakebackRequested { by }) => {
+                            online.takeback_requested_by = Some(by);
+                        }
+                        network::Event::Server(ServerMessage::TakebackDeclined { .. }) => {
+                            online.takeback_requested_by = None;
+                            online.status = tr("takeback-declined");
+                        }
+                        network::Event::Server(ServerMessage::Chat { from, text }) => {
+                            online.status = match from {
+                                Some(player) => tr_args(
+                                    "chat",
+                                    &[("player", name(player).into()), ("text", text.into())],
+                                ),
+                                None => text,
+                            };
+                        }
+                        network::Event::Server(ServerMessage::Error(error)) => {
+                            online.status = server_error(error);
+                        }
+                        network::Event::Server(_) => {}
+                        network::Event::Disconnected => {
+                            online.status = tr("reconnecting");
+                        }
+                        network::Event::Error(message) => {
+                            online.status = message;
+                        }
+                    }
+                }
+                Message::Resign => state.send(ClientMessage::Resign),
+                Message::OfferDraw => state.send(ClientMessage::OfferDraw),
+                Message::AcceptDraw => state.send(ClientMessage::AcceptDraw),
+                Message::DeclineDraw => {
+                    state.send(ClientMessage::DeclineDraw);
+                    if let Some(online) = &mut state.online {
+                        online.draw_offered_by = None;
+                    }
+                }
+                // Online games ask the opponent instead of undoing straight away
+                Message::Undo if state.online.is_some() => {
+                    state.send(ClientMessage::RequestTakeback)
+                }
+                Message::Undo if !state.clock_paused => state.undo(),
+                Message::AcceptTakeback => state.send(ClientMessage::AcceptTakeback),
+                Message::DeclineTakeback => {
+                    state.send(ClientMessage::DeclineTakeback);
+                    if let Some(online) = &mut state.online {
+                        online.takeback_requested_by = None;
+                    }
+                }
+                Message::Lesson(lesson) if state.tutorial.is_some() => state.start_lesson(lesson),
+                Message::Puzzle(index) if state.puzzle.is_some() => state.start_puzzle(index),
+                Message::Rotate => state.rotation = (state.rotation + 1) % 6,
+                Message::Flip => state.flipped = !state.flipped,
+                Message::CoordinatesToggled(coordinates) => {
+                    state.coordinates = coordinates;
+                    let mut settings = Settings::load();
+                    settings.coordinates = coordinates;
+                    if let Err(message) = settings.save() {
+                        state.notice = tr_args("settings-not-saved", &[("error", message.into())]);
+                    }
                 }
                 _ => {}
             },
@@ -172,24 +995,90 @@ impl Application for SixesUI {
         Command::none()
     }
 
-    fn view(&self) -> Element<Self::Message> {
+    fn view(&self) -> Element<'_, Self::Message> {
         match self {
-            SixesUI::Menu => column!(
+            SixesUI::Menu(menu) => column!(
                 text("Sixes").size(50),
                 column!(
-                        text("Rules").size(32), 
-                        text("Objective: Maintain a three in a row for two turns straight."),
-                        text("On your turn you may:"),
-                        text("1. Play a stone on an empty hex. If this hex is not the center hex you get two stones back."),
-                        text("2. Play n + 1 stones on an occupied hex with n stones of the opposite colour. The other stones are removed from the board."),
-                        text("3. Play a king on a hex with any number of stones of the opposite colour except the center hex."),
-                        text("On your turn you may not:"),
-                        text("Play on a hex that has been played on in the last turn."),
+                    text(tr("rules")).size(32),
+                    text(tr("rules-objective")),
+                    text(tr("rules-may")),
+                    text(tr("rules-empty")),
+                    text(tr("rules-capture")),
+                    text(tr("rules-king")),
+                    text(tr("rules-may-not")),
+                    text(tr("rules-last-played")),
+                    button(text(tr("tutorial"))).on_press(Message::Tutorial),
+                )
+                .width(Length::Fixed(300.0))
+                .spacing(10)
+                .align_items(Alignment::Center),
+                row![
+                    pick_list(
+                        &ClockSetting::ALL[..],
+                        Some(menu.clock),
+                        Message::ClockSelected
+                    ),
+                    pick_list(
+                        &HintSetting::ALL[..],
+                        Some(menu.hints),
+                        Message::HintsSelected
+                    ),
+                    button(text(tr("start"))).on_press(Message::Start),
+                    nav_button(tr("load"), menu.can_load.then_some(Message::Load)),
+                    nav_button(tr("resume"), menu.can_resume.then_some(Message::Resume)),
+                    button(text(tr("puzzles"))).on_press(Message::Puzzles),
+                ]
+                .spacing(10),
+                row![
+                    text_input(&tr("moves-placeholder"), &menu.position)
+                        .on_input(Message::PositionChanged)
+                        .on_submit(Message::Analyse),
+                    button(text(tr("analyse"))).on_press(Message::Analyse),
+                ]
+                .width(Length::Fixed(400.0))
+                .spacing(10),
+                row![
+                    pick_list(
+                        &ThemeSetting::ALL[..],
+                        Some(menu.settings.theme),
+                        Message::ThemeSelected
+                    ),
+                    checkbox(
+                        tr("animations"),
+                        menu.settings.animations,
+                        Message::AnimationsToggled
+                    ),
+                    checkbox(
+                        tr("coordinates"),
+                        menu.settings.coordinates,
+                        Message::CoordinatesToggled
+                    ),
+                    pick_list(
+                        &Language::ALL[..],
+                        Some(menu.settings.language),
+                        Message::LanguageSelected
+                    ),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+                text(&menu.notice),
+                column!(
+                    text(tr("join-online")).size(32),
+                    text_input(&tr("host"), &menu.host).on_input(Message::HostChanged),
+                    text_input(&tr("room"), &menu.room)
+                        .on_input(Message::RoomChanged)
+                        .on_submit(Message::JoinOnline),
+                    row![
+                        button(text(tr("create"))).on_press(Message::CreateOnline),
+                        button(text(tr("join"))).on_press(Message::JoinOnline),
+                        button(text(tr("watch"))).on_press(Message::WatchOnline),
+                    ]
+                    .spacing(10),
                 )
                 .width(Length::Fixed(300.0))
                 .spacing(10)
                 .align_items(Alignment::Center),
-                button("Start").on_press(Message::Start),
             )
             .width(Length::Fill)
             .height(Length::Fill)
@@ -199,74 +1088,481 @@ impl Application for SixesUI {
             .into(),
 
             SixesUI::Running(state) => {
-                let title_bar = match state.game.game_state() {
-                    GameState::Win(player) => {
-                        row![
-                            text(format!("Player {:?} wins!", player)).size(50),
-                            button("Menu").on_press(Message::Menu),
-                        ]
+                let outcome = state
+                    .online
+                    .as_ref()
+                    .and_then(|online| online.outcome.clone());
+                let title = match (outcome, state.game.game_state()) {
+                    (Some(Outcome::Resigned { winner }), _) => {
+                        tr_args("win-by-resignation", &[("player", name(winner).into())])
+                    }
+                    (Some(Outcome::Draw), _) => tr("draw-agreed"),
+                    (_, GameState::Win(player)) => {
+                        tr_args("win", &[("player", name(player).into())])
                     }
-                    GameState::Running => {
-                        row![
-                            text("Sixes").size(50),
-                            button("Menu").on_press(Message::Menu),
-                        ]
+                    (_, GameState::WinOnTime(player)) => {
+                        tr_args("win-on-time", &[("player", name(player).into())])
                     }
+                    (_, GameState::Running) => String::from("Sixes"),
                 };
-                let stone_amt_pl1 = text(format!("Stones: {}", state.player_one.stones()));
-                let stone_amt_pl2 = text(format!("Stones: {}", state.player_two.stones()));
+                let mut title_bar = row![
+                    text(title).size(50),
+                    button(text(tr("menu"))).on_press(Message::Menu)
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center);
+                if state.online.is_none() && !state.is_exercise() {
+                    let undo = !state.game.history().is_empty()
+                        && state.game.game_state() == GameState::Running;
+                    let analyse = if state.analysing {
+                        tr("stop-analysis")
+                    } else {
+                        tr("analyse")
+                    };
+                    title_bar = title_bar
+                        .push(button(text(analyse)).on_press(Message::Analyse))
+                        .push(nav_button(tr("undo"), undo.then_some(Message::Undo)))
+                        .push(hint_button(state))
+                        .push(button(text(tr("save"))).on_press(Message::Save))
+                        .push(text(&state.notice));
+                }
+                let stone_amt_pl1 = text(tr_args(
+                    "stones-in-hand",
+                    &[("count", state.player_one.stones().into())],
+                ));
+                let stone_amt_pl2 = text(tr_args(
+                    "stones-in-hand",
+                    &[("count", state.player_two.stones().into())],
+                ));
                 let player_one_control = row![
-                    text("Player One"),
-                    if state.player_one.stones() == 0
-                        || !(state.game.turn() == PlayerID::One)
-                        || state.game.game_state() != GameState::Running
-                    {
+                    text(name(PlayerID::One)),
+                    text(clock_label(state, PlayerID::One)),
+                    if state.player_one.stones() == 0 || !state.can_play(PlayerID::One) {
                         button(stone_amt_pl1)
                     } else {
                         button(stone_amt_pl1).on_press(Message::SelectStone)
                     },
-                    if state.player_one.king()
-                        && state.game.turn() == PlayerID::One
-                        && state.game.game_state() == GameState::Running
-                    {
-                        button("King").on_press(Message::SelectKing)
+                    if state.player_one.king() && state.can_play(PlayerID::One) {
+                        button(text(tr("king"))).on_press(Message::SelectKing)
                     } else {
-                        button("King")
+                        button(text(tr("king")))
                     },
                 ];
                 let board = Canvas::new(HexagonGridCanvas {
-                    grid: HexagonGrid::new(5.0, 5.0),
-                    state: state.clone(),
+                    grid: state.grid(),
+                    state: state.as_ref().clone(),
                 })
-                .width(Length::Fixed(800.0))
+                .width(Length::Fill)
                 .height(Length::Fill);
                 let player_two_control = row![
-                    text("Player Two"),
-                    if state.player_two.stones() == 0
-                        || !(state.game.turn() == PlayerID::Two)
-                        || state.game.game_state() != GameState::Running
-                    {
+                    text(name(PlayerID::Two)),
+                    text(clock_label(state, PlayerID::Two)),
+                    if state.player_two.stones() == 0 || !state.can_play(PlayerID::Two) {
                         button(stone_amt_pl2)
                     } else {
                         button(stone_amt_pl2).on_press(Message::SelectStone)
                     },
-                    if state.player_two.king()
-                        && state.game.turn() == PlayerID::Two
-                        && state.game.game_state() == GameState::Running
-                    {
-                        button("King").on_press(Message::SelectKing)
+                    if state.player_two.king() && state.can_play(PlayerID::Two) {
+                        button(text(tr("king"))).on_press(Message::SelectKing)
                     } else {
-                        button("King")
+                        button(text(tr("king")))
                     },
                 ];
 
-                column![title_bar, player_one_control, board, player_two_control,]
+                let mut layout = column![title_bar];
+                if let Some(online) = &state.online {
+                    layout = layout.push(online_bar(online));
+                }
+                if let Some(tutorial) = &state.tutorial {
+                    layout = layout.push(tutorial_bar(tutorial));
+                }
+                if let Some(attempt) = &state.puzzle {
+                    layout = layout.push(puzzle_bar(attempt));
+                }
+                if state.analysing {
+                    layout = layout.push(analysis_bar(state));
+                }
+                let shown = state.shown_game();
+                let focus = match state.focus {
+                    Some(coord) => tr_args(
+                        "focus",
+                        &[("hex", describe::describe_hex(&shown, coord).into())],
+                    ),
+                    None => tr("keyboard-help"),
+                };
+                let summary = column![
+                    text(describe::describe_last_move(&shown)).size(16),
+                    text(describe::describe_board(&shown)).size(16),
+                    text(focus).size(16),
+                ]
+                .width(Length::Fill);
+                let (top_control, bottom_control) = match state.top_player() {
+                    PlayerID::One => (player_one_control, player_two_control),
+                    PlayerID::Two => (player_two_control, player_one_control),
+                };
+                let play_area =
+                    column![view_bar(state), top_control, board, bottom_control, summary]
+                        .width(Length::Fill)
+                        .align_items(Alignment::Center);
+                layout
+                    .push(row![play_area, history_panel(state)].spacing(10))
                     .width(Length::Fill)
                     .align_items(Alignment::Center)
                     .into()
             }
         }
     }
+
+    fn subscription(&self) -> Subscription<Message> {
+        match self {
+            SixesUI::Running(state) => {
+                let network = match &state.online {
+                    Some(online) => network::connect(online.target.clone()).map(Message::Network),
+                    None => Subscription::none(),
+                };
+                let clock = if state.game.clock().is_some()
+                    && state.game.game_state() == GameState::Running
+                {
+                    time::every(Duration::from_millis(100)).map(Message::Tick)
+                } else {
+                    Subscription::none()
+                };
+                let analysis = if state.analysing {
+                    analysis::analyse(state.shown_game().history()).map(Message::Analysis)
+                } else {
+                    Subscription::none()
+                };
+                let animation = if state.animation.is_some() {
+                    time::every(Duration::from_millis(16)).map(Message::Animate)
+                } else {
+                    Subscription::none()
+                };
+                let keys = subscription::events_with(|event, status| match (event, status) {
+                    (
+                        iced::Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }),
+                        event::Status::Ignored,
+                    ) => Some(Message::Key(key_code)),
+                    _ => None,
+                });
+                Subscription::batch([network, clock, analysis, animation, keys])
+            }
+            SixesUI::Menu(_) => Subscription::none(),
+        }
+    }
+}
+
+// Why typed or received moves could not be read, for notices
+fn notation_error(error: ParseMoveError) -> String {
+    tr_args("unreadable-move", &[("move", error.0.into())])
+}
+
+fn record_error(error: RecordError) -> String {
+    match error {
+        RecordError::InvalidTag(tag) => tr_args("unknown-tag", &[("tag", tag.into())]),
+        RecordError::InvalidMoves(moves) => tr_args("unreadable-move", &[("move", moves.into())]),
+        RecordError::IllegalMove => tr("illegal-moves"),
+    }
+}
+
+// Why the server turned down a request
+fn server_error(error: ServerError) -> String {
+    match error {
+        ServerError::Malformed => tr("server-malformed"),
+        ServerError::RoomExists { room } => tr_args("room-exists", &[("room", room.into())]),
+        ServerError::NoSuchRoom { room } => tr_args("no-such-room", &[("room", room.into())]),
+        ServerError::RoomFull { room } => tr_args("room-full", &[("room", room.into())]),
+        ServerError::SeatTaken { seat } => tr_args("seat-taken", &[("player", name(seat).into())]),
+        ServerError::NotInRoom => tr("not-in-room"),
+        ServerError::NotSeated => tr("not-seated"),
+        ServerError::InvalidMove { notation } => {
+            tr_args("unreadable-move", &[("move", notation.into())])
+        }
+        ServerError::IllegalMove { notation } => {
+            tr_args("illegal-move", &[("move", notation.into())])
+        }
+        ServerError::NotYourTurn => tr("not-your-turn"),
+        ServerError::GameOver => tr("game-over"),
+        ServerError::NoDrawOffer => tr("no-draw-offer"),
+        ServerError::NothingToTakeBack => tr("nothing-to-take-back"),
+        ServerError::NoTakebackRequest => tr("no-takeback-request"),
+    }
+}
+
+fn clock_label(state: &State, player: PlayerID) -> String {
+    match state.game.clock() {
+        Some(clock) => clock_text(clock, player),
+        None => String::new(),
+    }
+}
+
+// Room, seat and connection status, plus resign and draw controls for the seated player
+fn online_bar(online: &Online) -> Element<'_, Message> {
+    let seat = match online.seat {
+        Some(player) => tr_args(
+            "room-seat",
+            &[
+                ("room", online.target.room.as_str().into()),
+                ("player", name(player).into()),
+            ],
+        ),
+        None => tr_args("room-only", &[("room", online.target.room.as_str().into())]),
+    };
+    let watching = match online.spectators {
+        0 => String::new(),
+        n => tr_args("watching", &[("count", n.into())]),
+    };
+    let mut bar = row![text(seat), text(watching), text(&online.status)].spacing(10);
+    if let (Some(player), None) = (online.seat, &online.outcome) {
+        bar = bar.push(button(text(tr("resign"))).on_press(Message::Resign));
+        bar = match online.draw_offered_by {
+            Some(by) if by != player => bar
+                .push(button(text(tr("accept-draw"))).on_press(Message::AcceptDraw))
+                .push(button(text(tr("decline-draw"))).on_press(Message::DeclineDraw)),
+            Some(_) => bar.push(button(text(tr("draw-offered")))),
+            None => bar.push(button(text(tr("offer-draw"))).on_press(Message::OfferDraw)),
+        };
+        bar = match online.takeback_requested_by {
+            Some(by) if by != player => bar
+                .push(button(text(tr("accept-takeback"))).on_press(Message::AcceptTakeback))
+                .push(button(text(tr("decline-takeback"))).on_press(Message::DeclineTakeback)),
+            Some(_) => bar.push(button(text(tr("takeback-requested")))),
+            None => bar.push(button(text(tr("undo"))).on_press(Message::Undo)),
+        };
+    }
+    bar.align_items(Alignment::Center).into()
+}
+
+// The lesson's explanation, feedback on the last try and the way on
+fn tutorial_bar(tutorial: &Tutorial) -> Element<'_, Message> {
+    let number = tutorial.number();
+    let lesson = tutorial.lesson();
+    let previous = number.checked_sub(1).map(Message::Lesson);
+    let next = (number + 1 < LESSONS.len()).then_some(Message::Lesson(number + 1));
+    let next_label = if tutorial.solved() {
+        tr("next-lesson")
+    } else {
+        tr("skip")
+    };
+    column![
+        text(tr_args(
+            "lesson-heading",
+            &[
+                ("number", (number + 1).into()),
+                ("total", LESSONS.len().into()),
+                ("title", lesson.title().into()),
+            ],
+        ))
+        .size(24),
+        text(lesson.explanation()),
+        text(tutorial.feedback()),
+        row![
+            nav_button(tr("previous"), previous),
+            button(text(tr("try-again"))).on_press(Message::Lesson(number)),
+            nav_button(next_label, next),
+        ]
+        .spacing(10),
+    ]
+    .spacing(5)
+    .padding(5)
+    .into()
+}
+
+// The goal, how the attempt is going and the way to other puzzles
+fn puzzle_bar(attempt: &Attempt) -> Element<'_, Message> {
+    let total = puzzles::all().len();
+    let number = attempt.number();
+    let solved = puzzles::solved();
+    let solved_before = solved.contains(&attempt.puzzle().to_line());
+    let solved_count = puzzles::all()
+        .iter()
+        .filter(|puzzle| solved.contains(&puzzle.to_line()))
+        .count();
+    let previous = number.checked_sub(1).map(Message::Puzzle);
+    let next = (number + 1 < total).then_some(Message::Puzzle(number + 1));
+    let next_label = if attempt.status() == puzzles::Status::Solved {
+        tr("next-puzzle")
+    } else {
+        tr("skip")
+    };
+    let mut heading = tr_args(
+        "puzzle-heading",
+        &[
+            ("number", (number + 1).into()),
+            ("total", total.into()),
+            ("player", name(attempt.player()).into()),
+            ("goal", puzzles::goal_text(attempt.goal()).into()),
+        ],
+    );
+    if solved_before {
+        heading = format!("{} {}", heading, tr("solved-before"));
+    }
+    column![
+        text(heading).size(24),
+        text(attempt.feedback()),
+        row![
+            nav_button(tr("previous"), previous),
+            button(text(tr("try-again"))).on_press(Message::Puzzle(number)),
+            nav_button(next_label, next),
+            text(tr_args(
+                "puzzles-solved",
+                &[("count", solved_count.into()), ("total", total.into())],
+            )),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    ]
+    .spacing(5)
+    .padding(5)
+    .into()
+}
+
+// Orientation of the board on screen; none of it changes the game
+fn view_bar(state: &State) -> Element<'_, Message> {
+    row![
+        button(text(tr("rotate"))).on_press(Message::Rotate),
+        button(text(tr("flip"))).on_press(Message::Flip),
+        checkbox(
+            tr("coordinates"),
+            state.coordinates,
+            Message::CoordinatesToggled
+        ),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center)
+    .into()
+}
+
+// Evaluation, expected line and per-move scores from the background engine
+fn analysis_bar(state: &State) -> Element<'_, Message> {
+    let Some(report) = state.report() else {
+        return text(tr("analysing")).into();
+    };
+    let turn = state.shown_game().turn();
+    let summary = match report.eval() {
+        Some(eval) => tr_args(
+            "evaluation",
+            &[
+                ("depth", report.depth.into()),
+                ("eval", format!("{:+.2}", eval).into()),
+                ("player", name(turn).into()),
+            ],
+        ),
+        None => tr("no-moves-to-analyse"),
+    };
+    let line = report
+        .line
+        .iter()
+        .map(|(coord, play)| move_to_string(*coord, *play))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let scores = report
+        .scores
+        .iter()
+        .map(|(coord, play, score)| format!("{} {:+.2}", move_to_string(*coord, *play), score))
+        .collect::<Vec<_>>()
+        .join("   ");
+    column![
+        text(summary),
+        text(tr_args("best-line", &[("line", line.into())])),
+        text(scores).size(16),
+    ]
+    .align_items(Alignment::Center)
+    .into()
+}
+
+// Move list down the side of the board. Each entry shows the position after
+// that move; kings are marked by the notation's K and captures with an x.
+fn history_panel(state: &State) -> Element<'_, Message> {
+    let history = state.game.history();
+    let shown = state.viewing.unwrap_or(history.len());
+    let entry = |label: String, ply: usize| {
+        let target = if ply == history.len() {
+            None
+        } else {
+            Some(ply)
+        };
+        button(text(label))
+            .width(Length::Fill)
+            .style(if ply == shown {
+                theme::Button::Primary
+            } else {
+                theme::Button::Secondary
+            })
+            .on_press(Message::ViewMove(target))
+    };
+
+    let mut moves = column![entry(tr("start"), 0)].spacing(2);
+    let mut replay = Sixes::start();
+    for (i, (coord, play)) in history.iter().enumerate() {
+        let captured = replay
+            .board()
+            .iter()
+            .any(|(hex, _, _, owner)| hex == coord && *owner == Some(replay.turn().other()));
+        let mark = if captured { "x" } else { "" };
+        moves = moves.push(entry(
+            format!("{}. {}{}", i + 1, move_to_string(*coord, *play), mark),
+            i + 1,
+        ));
+        // The history is only ever built from legal moves
+        let _ = replay.play(*coord, *play);
+    }
+
+    let to_move = match state.game.game_state() {
+        GameState::Running => tr_args("to-move", &[("player", name(state.game.turn()).into())]),
+        GameState::Win(_) | GameState::WinOnTime(_) => tr("game-over"),
+    };
+    let back = shown.checked_sub(1).map(|ply| Message::ViewMove(Some(ply)));
+    let forward = (shown < history.len()).then(|| {
+        Message::ViewMove(if shown + 1 == history.len() {
+            None
+        } else {
+            Some(shown + 1)
+        })
+    });
+    // Branching is for local games; online the server's game is authoritative
+    let resume = (state.viewing.is_some() && state.online.is_none()).then_some(Message::Branch);
+
+    column![
+        text(to_move),
+        row![
+            nav_button("<<", back.as_ref().map(|_| Message::ViewMove(Some(0)))),
+            nav_button("<", back),
+            nav_button(">", forward.clone()),
+            nav_button(">>", forward.map(|_| Message::ViewMove(None))),
+        ]
+        .spacing(5),
+        text(format!("{}/{}", shown, history.len())),
+        scrollable(moves).height(Length::Fill),
+        nav_button(tr("resume-from-here"), resume),
+    ]
+    .width(Length::Fixed(170.0))
+    .spacing(10)
+    .align_items(Alignment::Center)
+    .into()
+}
+
+fn hint_button(state: &State) -> iced::widget::Button<'_, Message> {
+    let player = state.game.turn();
+    let label = match state.hints_left(player) {
+        Some(left) => tr_args("hints-left", &[("count", left.into())]),
+        None => tr("hint"),
+    };
+    let enabled = state.can_play(player) && state.hints_left(player) != Some(0);
+    if enabled {
+        button(text(label)).on_press(Message::Hint)
+    } else {
+        button(text(label))
+    }
+}
+
+fn nav_button<'a>(
+    label: impl ToString,
+    message: Option<Message>,
+) -> iced::widget::Button<'a, Message> {
+    match message {
+        Some(message) => button(text(label)).on_press(message),
+        None => button(text(label)),
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -285,6 +1581,20 @@ impl Program<Message> for HexagonGridCanvas {
         bounds: iced::Rectangle,
         cursor: Cursor,
     ) -> (event::Status, Option<Message>) {
+        if !self.state.accepts_input() {
+            return (event::Status::Ignored, None);
+        }
+        // Touch screens have no hover, so a tap both picks the hex and plays it
+        if let Event::Touch(touch::Event::FingerPressed { position, .. }) = event {
+            if !bounds.contains(position) {
+                return (event::Status::Ignored, None);
+            }
+            let position = Point::new(position.x - bounds.x, position.y - bounds.y);
+            return match self.grid.in_hexagon(position, bounds.size()) {
+                Some(coord) => (event::Status::Captured, Some(Message::TapHex(coord))),
+                None => (event::Status::Ignored, None),
+            };
+        }
         let cursor_position = if let Some(position) = cursor.position_in(&bounds) {
             position
         } else {
@@ -296,7 +1606,7 @@ impl Program<Message> for HexagonGridCanvas {
                     (event::Status::Captured, Some(Message::SelectHex))
                 }
                 mouse::Event::CursorMoved { .. } => {
-                    let coord = self.grid.in_hexagon(cursor_position, bounds.width);
+                    let coord = self.grid.in_hexagon(cursor_position, bounds.size());
                     if let Some(coord) = coord {
                         (event::Status::Captured, Some(Message::OverHex(Some(coord))))
                     } else {
@@ -316,33 +1626,192 @@ impl Program<Message> for HexagonGridCanvas {
         bounds: iced::Rectangle,
         _cursor: Cursor,
     ) -> Vec<Geometry> {
-        let hex_frame = self.grid.draw_hexes(bounds);
+        let palette = self.state.palette;
+        let hex_frame = self
+            .grid
+            .draw_hexes(bounds, &palette, self.state.coordinates);
+
+        let shades = self
+            .state
+            .report()
+            .map(analysis::Report::shades)
+            .unwrap_or_default();
+        let shade_frame = self.grid.shade_hexes(bounds, &shades);
 
         let mut circle_frame: Frame = Frame::new(bounds.size());
 
         let circles = self
             .grid
-            .draw_circles(bounds.width, self.state.hexes_selectable.clone());
+            .draw_circles(bounds.size(), self.state.hexes_selectable.clone());
         for circle in circles {
             circle_frame.stroke(
                 &circle,
                 Stroke::default()
                     .with_width(2.0)
-                    .with_color(Color::from_rgb(1.0, 0.0, 0.0)),
+                    .with_color(palette.selectable),
             );
         }
 
-        let piece_frame = self.grid.draw_pieces(
+        if let Some(focus) = self.state.focus {
+            for circle in self.grid.draw_circles(bounds.size(), vec![focus]) {
+                circle_frame.stroke(
+                    &circle,
+                    Stroke {
+                        line_dash: LineDash {
+                            segments: &[6.0, 6.0],
+                            offset: 0,
+                        },
+                        ..Stroke::default().with_width(4.0).with_color(palette.focus)
+                    },
+                );
+            }
+        }
+
+        if let Some(hint) = self.state.hint {
+            for circle in self.grid.draw_circles(bounds.size(), vec![hint]) {
+                circle_frame.stroke(
+                    &circle,
+                    Stroke::default().with_width(5.0).with_color(palette.hint),
+                );
+            }
+        }
+
+        // While hovering a legal hex the board is drawn as it would be after the move
+        let preview = self.state.preview();
+        let mut board = match &preview {
+            Some((_, preview)) => preview.after.board(),
+            None => self.state.shown_game().board(),
+        };
+        // A moving piece is drawn by its animation rather than on its hex
+        let animation = self
+            .state
+            .animation
+            .as_ref()
+            .filter(|_| self.state.viewing.is_none());
+        if let Some(coord) = animation.and_then(Animation::coord) {
+            board.retain(|(hex, _, _, _)| *hex != coord);
+        }
+        let mut piece_frame = self.grid.draw_pieces(
             bounds,
-            self.state.game.board(),
+            board,
             self.state.player_one,
             self.state.player_two,
+            preview.as_ref().map(|(coord, _)| *coord),
+            palette.marked,
         );
+        if let Some((coord, preview)) = &preview {
+            let mut effects = Vec::new();
+            if preview.stack > 0 {
+                effects.push(tr_args("stones", &[("count", preview.stack.into())]));
+            }
+            if preview.removed > 0 {
+                effects.push(tr_args(
+                    "preview-takes",
+                    &[("count", preview.removed.into())],
+                ));
+            }
+            if preview.bonus > 0 {
+                effects.push(tr_args("preview-stock", &[("count", preview.bonus.into())]));
+            }
+            if !preview.triples.is_empty() {
+                effects.push(tr("preview-triple"));
+            }
+            let center = self.grid.center(bounds.size(), *coord);
+            piece_frame.fill_text(canvas::Text {
+                content: effects.join(", "),
+                position: Point::new(center.x, center.y - self.grid.radius(bounds.size()) / 2.0),
+                size: 20.0,
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Center,
+                ..canvas::Text::default()
+            });
+            for triple in &preview.triples {
+                piece_frame.stroke(
+                    &self.grid.draw_line(bounds.size(), triple.coords()),
+                    Stroke {
+                        line_dash: LineDash {
+                            segments: &[4.0, 6.0],
+                            offset: 0,
+                        },
+                        ..Stroke::default()
+                            .with_width(5.0)
+                            .with_color(palette.preview_triple)
+                    },
+                );
+            }
+        }
+
+        // Solid lines for triples on the board, thick for those that win
+        // unless the side to move breaks them, dashed for triples the side to
+        // move can complete
+        let mut line_frame = Frame::new(bounds.size());
+        let game = self.state.shown_game();
+        for triple in game.triples() {
+            let stroke = if triple.player() != game.turn() {
+                Stroke::default()
+                    .with_width(8.0)
+                    .with_color(palette.winning_triple)
+            } else {
+                Stroke::default()
+                    .with_width(5.0)
+                    .with_color(palette.own_triple)
+            };
+            line_frame.stroke(&self.grid.draw_line(bounds.size(), triple.coords()), stroke);
+        }
+        if game.game_state() == GameState::Running {
+            for (_, _, triple) in game.threats() {
+                line_frame.stroke(
+                    &self.grid.draw_line(bounds.size(), triple.coords()),
+                    Stroke {
+                        line_dash: LineDash {
+                            segments: &[12.0, 8.0],
+                            offset: 0,
+                        },
+                        ..Stroke::default().with_width(3.0).with_color(palette.threat)
+                    },
+                );
+            }
+        }
+
+        let animation_frame = match animation {
+            Some(animation) => animation.draw(
+                &self.grid,
+                bounds,
+                self.state.player_one,
+                self.state.player_two,
+                self.state.top_player(),
+                &palette,
+            ),
+            None => Frame::new(bounds.size()),
+        };
 
         vec![
             hex_frame.into_geometry(),
+            shade_frame.into_geometry(),
             circle_frame.into_geometry(),
             piece_frame.into_geometry(),
+            line_frame.into_geometry(),
+            animation_frame.into_geometry(),
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn player_one_sits_at_the_bottom_unless_flipped() {
+        let mut state = State::new(Sixes::start(), None);
+        assert_eq!(state.top_player(), PlayerID::Two);
+        assert_eq!(state.bottom_player(), PlayerID::One);
+        state.flipped = true;
+        assert_eq!(state.top_player(), PlayerID::One);
+        assert_eq!(state.bottom_player(), PlayerID::Two);
+
+        for player in [PlayerID::One, PlayerID::Two] {
+            state.set_bottom_player(player);
+            assert_eq!(state.bottom_player(), player);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use std::sync::mpsc;
+use std::time::Duration;
+
+use iced::futures::channel::mpsc as async_mpsc;
+use iced::futures::{SinkExt, StreamExt};
+use iced::{subscription, Subscription};
+use sixes_game::PlayerID;
+use sixes_protocol::{ClientMessage, ServerMessage, Snapshot};
+
+#[cfg(target_arch = "wasm32")]
+use crate::locale::tr;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::locale::tr_args;
+
+// How long the socket blocks on reading before queued moves are sent
+#[cfg(not(target_arch = "wasm32"))]
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+// Handle for sending to the server; the connection thread owns the socket
+#[derive(Debug, Clone)]
+pub struct Connection {
+    sender: mpsc::Sender<ClientMessage>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    Connected(Connection),
+    Joined(Option<PlayerID>),
+    State(Snapshot),
+    Server(ServerMessage),
+    Disconnected,
+    Error(String),
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum JoinMode {
+    Create,
+    Join,
+    Watch,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Target {
+    pub host: String,
+    pub room: String,
+    pub mode: JoinMode,
+}
+
+impl Connection {
+    pub fn send(&self, message: ClientMessage) {
+        // A closed channel means the connection thread is reconnecting or gone,
+        // the next state push resyncs the board either way
+        let _ = self.sender.send(message);
+    }
+}
+
+// Keeps a connection to `target` open for as long as the subscription is
+// active. After a drop it reconnects and rejoins the same seat; the server
+// answers with a full state push, so the board is rebuilt from scratch.
+pub fn connect(target: Target) -> Subscription<Event> {
+    subscription::channel(target.clone(), 100, move |mut output| {
+        let target = target.clone();
+        async move {
+            let (events, mut receiver) = async_mpsc::unbounded();
+            spawn_connection(target, events);
+            loop {
+                match receiver.next().await {
+                    Some(event) => {
+                        let _ = output.send(event).await;
+                    }
+                    None => iced::futures::future::pending::<()>().await,
+                }
+            }
+        }
+    })
+}
+
+#[cfg(target_arch = "wasm32")]
+fn spawn_connection(_target: Target, events: async_mpsc::UnboundedSender<Event>) {
+    let _ = events.unbounded_send(Event::Error(tr("online-unavailable")));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_connection(target: Target, events: async_mpsc::UnboundedSender<Event>) {
+    std::thread::spawn(move || {
+        let (sender, receiver) = mpsc::channel();
+        let room = target.room.clone();
+        let mut join = match target.mode {
+            JoinMode::Create => ClientMessage::Create { room },
+            JoinMode::Join => ClientMessage::Join {
+                room,
+                seat: None,
+                token: None,
+            },
+            JoinMode::Watch => ClientMessage::Watch { room },
+        };
+        if events
+            .unbounded_send(Event::Connected(Connection { sender }))
+            .is_err()
+        {
+            return;
+        }
+
+        loop {
+            match run_connection(&target.host, &mut join, &receiver, &events) {
+                // The app dropped the subscription
+                Ok(false) => return,
+                Ok(true) => {}
+                Err(message) => {
+                    if events.unbounded_send(Event::Error(message)).is_err() {
+                        return;
+                    }
+                }
+            }
+            if events.unbounded_send(Event::Disconnected).is_err() {
+                return;
+            }
+            std::thread::sleep(RECONNECT_DELAY);
+        }
+    });
+}
+
+// Runs one connection until it drops, returning false once the app is no
+// longer listening. Once in the room, `join` is rewritten to rejoin the same
+// seat with the server's rejoin token, or to watch again for spectators, so
+// that a reconnect reclaims it.
+#[cfg(not(target_arch = "wasm32"))]
+fn run_connection(
+    host: &str,
+    join: &mut ClientMessage,
+    receiver: &mpsc::Receiver<ClientMessage>,
+    events: &async_mpsc::UnboundedSender<Event>,
+) -> Result<bool, String> {
+    use sixes_protocol::{decode_server, encode_client};
+    use std::io;
+    use tungstenite::stream::MaybeTlsStream;
+    use tungstenite::{Error, Message};
+
+    let url = format!("ws://{}", host);
+    let (mut socket, _) = tungstenite::connect(&url).map_err(|e| e.to_string())?;
+    if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+        stream
+            .set_read_timeout(Some(POLL_INTERVAL))
+            .map_err(|e| e.to_string())?;
+    }
+    socket
+        .send(Message::Text(encode_client(join)))
+        .map_err(|e| e.to_string())?;
+
+    // Messages queued while disconnected were made against a stale board
+    receiver.try_iter().for_each(drop);
+
+    loop {
+        let event = match socket.read() {
+            Ok(Message::Text(text)) => match decode_server(&text) {
+                Ok(ServerMessage::Joined { room, seat, token }) => {
+                    *join = match seat {
+                        Some(_) => ClientMessage::Join { room, seat, token },
+                        None => ClientMessage::Watch { room },
+                    };
+                    Some(Event::Joined(seat))
+                }
+                Ok(ServerMessage::State(snapshot)) => Some(Event::State(snapshot)),
+                Ok(message) => Some(Event::Server(message)),
+                Err(e) => Some(Event::Error(tr_args(
+                    "malformed-message",
+                    &[("error", e.to_string().into())],
+                ))),
+            },
+            Ok(Message::Close(_)) => return Ok(true),
+            Ok(_) => None,
+            Err(Error::Io(e))
+                if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut =>
+            {
+                None
+            }
+            Err(Error::ConnectionClosed) => return Ok(true),
+            Err(e) => return Err(e.to_string()),
+        };
+        if let Some(event) = event {
+            if events.unbounded_send(event).is_err() {
+                return Ok(false);
+            }
+        }
+
+        loop {
+            match receiver.try_recv() {
+                Ok(message) => socket
+                    .write(Message::Text(encode_client(&message)))
+                    .map_err(|e| e.to_string())?,
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => return Ok(false),
+            }
+        }
+        match socket.flush() {
+            Ok(()) => {}
+            Err(Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => {}
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+
+use iced::Color;
+
+use crate::locale::tr;
+
+// Colour schemes for the board, selectable from the menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeSetting {
+    Classic,
+    Dark,
+    HighContrast,
+    ColourBlind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub player_one: Color,
+    pub player_two: Color,
+    pub board_line: Color,
+    pub centre: Color,
+    pub selectable: Color,
+    pub hint: Color,
+    pub focus: Color,
+    // Triples that win unless the side to move breaks them, and the side to
+    // move's own triples
+    pub winning_triple: Color,
+    pub own_triple: Color,
+    // Triples the side to move could complete, and those a previewed move makes
+    pub threat: Color,
+    pub preview_triple: Color,
+    // Flashes over the winning triples; the animation sets the opacity
+    pub win_pulse: Color,
+    // Use iced's dark theme for the widgets around the board
+    pub dark: bool,
+    // Mark Player Two's pieces with a ring so they differ by shape, not only colour
+    pub marked: bool,
+}
+
+impl ThemeSetting {
+    pub const ALL: [ThemeSetting; 4] = [
+        ThemeSetting::Classic,
+        ThemeSetting::Dark,
+        ThemeSetting::HighContrast,
+        ThemeSetting::ColourBlind,
+    ];
+
+    pub fn palette(&self) -> Palette {
+        match self {
+            ThemeSetting::Classic => Palette {
+                player_one: Color::from_rgb8(0xFF, 0xFF, 0xFF),
+                player_two: Color::from_rgb8(0x00, 0x00, 0x00),
+                board_line: Color::BLACK,
+                centre: Color::from_rgb(1.0, 1.0, 0.8),
+                selectable: Color::from_rgb(1.0, 0.0, 0.0),
+                hint: Color::from_rgb(0.0, 0.4, 1.0),
+                focus: Color::from_rgb(0.6, 0.0, 0.8),
+                winning_triple: Color::from_rgba(0.9, 0.1, 0.1, 0.8),
+                own_triple: Color::from_rgba(0.9, 0.7, 0.0, 0.8),
+                threat: Color::from_rgba(0.2, 0.4, 0.9, 0.7),
+                preview_triple: Color::from_rgba(0.1, 0.7, 0.2, 0.9),
+                win_pulse: Color::from_rgb(1.0, 0.8, 0.0),
+                dark: false,
+                marked: false,
+            },
+            ThemeSetting::Dark => Palette {
+                player_one: Color::from_rgb8(0xEE, 0xE8, 0xD5),
+                player_two: Color::from_rgb8(0x58, 0x6E, 0x75),
+                board_line: Color::from_rgb8(0xC0, 0xC0, 0xC0),
+                centre: Color::from_rgb8(0x4A, 0x45, 0x2A),
+                selectable: Color::from_rgb8(0xFF, 0x6B, 0x6B),
+                hint: Color::from_rgb8(0x4D, 0xA6, 0xFF),
+                focus: Color::from_rgb8(0xD0, 0x8C, 0xFF),
+                winning_triple: Color::from_rgba8(0xFF, 0x55, 0x55, 0.85),
+                own_triple: Color::from_rgba8(0xFF, 0xC9, 0x40, 0.85),
+                threat: Color::from_rgba8(0x6F, 0xA8, 0xFF, 0.8),
+                preview_triple: Color::from_rgba8(0x5F, 0xD0, 0x68, 0.9),
+                win_pulse: Color::from_rgb8(0xFF, 0xD5, 0x4F),
+                dark: true,
+                marked: false,
+            },
+            ThemeSetting::HighContrast => Palette {
+                player_one: Color::WHITE,
+                player_two: Color::BLACK,
+                board_line: Color::BLACK,
+                centre: Color::from_rgb8(0xFF, 0xFF, 0x00),
+                selectable: Color::from_rgb8(0xFF, 0x00, 0xFF),
+                hint: Color::from_rgb8(0x00, 0x00, 0xFF),
+                focus: Color::from_rgb8(0x00, 0xA0, 0x00),
+                winning_triple: Color::from_rgb8(0xFF, 0x00, 0x00),
+                own_triple: Color::from_rgb8(0xFF, 0x80, 0x00),
+                threat: Color::from_rgb8(0x00, 0x00, 0xFF),
+                preview_triple: Color::from_rgb8(0x00, 0x80, 0x80),
+                win_pulse: Color::from_rgb8(0xFF, 0x80, 0x00),
+                dark: false,
+                marked: true,
+            },
+            // Okabe-Ito colours, which stay apart under the common colour deficiencies
+            ThemeSetting::ColourBlind => Palette {
+                player_one: Color::from_rgb8(0xE6, 0x9F, 0x00),
+                player_two: Color::from_rgb8(0x00, 0x72, 0xB2),
+                board_line: Color::BLACK,
+                centre: Color::from_rgb8(0xF0, 0xE4, 0x42),
+                selectable: Color::from_rgb8(0xD5, 0x5E, 0x00),
+                hint: Color::from_rgb8(0x56, 0xB4, 0xE9),
+                focus: Color::from_rgb8(0xCC, 0x79, 0xA7),
+                winning_triple: Color::from_rgba8(0xD5, 0x5E, 0x00, 0.9),
+                own_triple: Color::from_rgba8(0xF0, 0xE4, 0x42, 0.9),
+                threat: Color::from_rgba8(0x56, 0xB4, 0xE9, 0.8),
+                preview_triple: Color::from_rgba8(0x00, 0x9E, 0x73, 0.9),
+                win_pulse: Color::from_rgb8(0xD5, 0x5E, 0x00),
+                dark: false,
+                marked: true,
+            },
+        }
+    }
+
+    // Name used in the settings file
+    pub fn key(&self) -> &'static str {
+        match self {
+            ThemeSetting::Classic => "classic",
+            ThemeSetting::Dark => "dark",
+            ThemeSetting::HighContrast => "high-contrast",
+            ThemeSetting::ColourBlind => "colour-blind",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        ThemeSetting::ALL
+            .into_iter()
+            .find(|setting| setting.key() == key)
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        ThemeSetting::Classic.palette()
+    }
+}
+
+impl fmt::Display for ThemeSetting {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            ThemeSetting::Classic => tr("theme-classic"),
+            ThemeSetting::Dark => tr("theme-dark"),
+            ThemeSetting::HighContrast => tr("theme-high-contrast"),
+            ThemeSetting::ColourBlind => tr("theme-colour-blind"),
+        };
+        write!(f, "{}", label)
+    }
+}
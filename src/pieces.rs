@@ -1,126 +1,353 @@
 use std::f32::consts::PI;
 
-use iced::widget::canvas::Path;
-use iced::Point;
+use iced::widget::canvas::{path::Builder, Frame, Path, Stroke, Text};
+use iced::{alignment, Color, Point};
 
-pub fn draw_stones(stones: u8, center: Point, hexagon_radius: f32) -> Vec<Path> {
-    let radius = hexagon_radius / 8.0;
-    let mut paths = Vec::new();
+// Stacks of up to 19 stones are packed in rings around the middle of the hex:
+// one in the centre, six around it and twelve around those
+const MAX_PACKED: u8 = 19;
+// Smallest stone worth drawing one by one; below this a stack is a count badge
+const MIN_STONE_RADIUS: f32 = 3.0;
 
+// Spacing between packed stones, as a fraction of the hexagon radius
+fn packed_spacing(stones: u8) -> f32 {
+    let rings = if stones <= 7 { 1.0 } else { 2.0 };
+    0.45 / (rings + 0.45)
+}
+
+// Whether a stack is too big, or the hex too small, to show every stone
+pub fn is_badge(stones: u8, hexagon_radius: f32) -> bool {
+    stones > MAX_PACKED
+        || (stones > 6 && hexagon_radius * packed_spacing(stones) * 0.45 < MIN_STONE_RADIUS)
+}
+
+pub fn stone_radius(stones: u8, hexagon_radius: f32) -> f32 {
+    if is_badge(stones, hexagon_radius) {
+        hexagon_radius / 4.0
+    } else if stones > 6 {
+        (hexagon_radius * packed_spacing(stones) * 0.45).min(hexagon_radius / 8.0)
+    } else {
+        hexagon_radius / 8.0
+    }
+}
+
+// Black or white, whichever stands out on `fill`
+pub fn count_color(fill: Color) -> Color {
+    let luminance = 0.299 * fill.r + 0.587 * fill.g + 0.114 * fill.b;
+    let color = if luminance > 0.5 {
+        Color::BLACK
+    } else {
+        Color::WHITE
+    };
+    Color { a: fill.a, ..color }
+}
+
+// Outline of a three-pointed crown standing on a band, centred on `center`
+pub fn crown_points(center: Point, hexagon_radius: f32) -> [Point; 7] {
+    let width = hexagon_radius / 2.0;
+    let height = hexagon_radius * 0.4;
+    let (left, right) = (center.x - width / 2.0, center.x + width / 2.0);
+    let (top, bottom) = (center.y - height / 2.0, center.y + height / 2.0);
+    // The dips between the points come down to just above the band
+    let dip = bottom - height * 0.45;
+    [
+        Point::new(left, bottom),
+        Point::new(left, top),
+        Point::new(center.x - width / 4.0, dip),
+        Point::new(center.x, top),
+        Point::new(center.x + width / 4.0, dip),
+        Point::new(right, top),
+        Point::new(right, bottom),
+    ]
+}
+
+fn polygon(points: &[Point]) -> Path {
+    let mut builder = Builder::new();
+    builder.move_to(points[0]);
+    for point in &points[1..] {
+        builder.line_to(*point);
+    }
+    builder.close();
+    builder.build()
+}
+
+// One shape of the pieces on a hex, with its fill and a 2 wide outline where
+// it has them. The board, its animations and the exported images all draw these.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Part {
+    pub shape: Shape,
+    pub fill: Option<Color>,
+    pub outline: Option<Color>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Circle(Point, f32),
+    Crown([Point; 7]),
+    // Stack size written on a badge, in a font of the given size
+    Count(u8, Point, f32),
+}
+
+// The pieces on a hex in drawing order: the stones, or the stack a king
+// captured in `other`, then the king. `mark` rings Player Two's pieces for
+// themes that tell the players apart by more than colour.
+pub fn piece_parts(
+    stones: u8,
+    king: bool,
+    color: Color,
+    other: Color,
+    mark: Option<Color>,
+    center: Point,
+    hexagon_radius: f32,
+) -> Vec<Part> {
+    let mut parts = Vec::new();
+    if stones > 0 {
+        let fill = if king { other } else { color };
+        let radius = stone_radius(stones, hexagon_radius);
+        let centers = stone_centers(stones, center, hexagon_radius);
+        for point in &centers {
+            parts.push(Part {
+                shape: Shape::Circle(*point, radius),
+                fill: Some(fill),
+                outline: Some(Color::BLACK),
+            });
+        }
+        if is_badge(stones, hexagon_radius) {
+            parts.push(Part {
+                shape: Shape::Count(stones, center, (hexagon_radius / 4.0).max(8.0)),
+                fill: Some(count_color(fill)),
+                outline: None,
+            });
+        }
+        if let (Some(mark), false) = (mark, king) {
+            for point in centers {
+                parts.push(Part {
+                    shape: Shape::Circle(point, radius / 2.0),
+                    fill: None,
+                    outline: Some(mark),
+                });
+            }
+        }
+    }
+    if king {
+        parts.push(Part {
+            shape: Shape::Crown(crown_points(center, hexagon_radius)),
+            fill: Some(color),
+            outline: Some(Color::BLACK),
+        });
+        if let Some(mark) = mark {
+            parts.push(Part {
+                shape: Shape::Circle(center, hexagon_radius / 16.0),
+                fill: Some(mark),
+                outline: None,
+            });
+        }
+    }
+    parts
+}
+
+pub fn draw_part(frame: &mut Frame, part: Part) {
+    let path = match part.shape {
+        Shape::Circle(center, radius) => Path::circle(center, radius),
+        Shape::Crown(points) => polygon(&points),
+        Shape::Count(stones, center, size) => {
+            frame.fill_text(Text {
+                content: stones.to_string(),
+                position: center,
+                color: part.fill.unwrap_or(Color::BLACK),
+                size,
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Center,
+                ..Text::default()
+            });
+            return;
+        }
+    };
+    if let Some(fill) = part.fill {
+        frame.fill(&path, fill);
+    }
+    if let Some(outline) = part.outline {
+        frame.stroke(&path, Stroke::default().with_width(2.0).with_color(outline));
+    }
+}
+
+// The first `stones` points of a hexagonal lattice around `center`, filling
+// each ring clockwise from the right before starting the next
+fn packed_centers(stones: u8, center: Point, hexagon_radius: f32) -> Vec<Point> {
+    let spacing = hexagon_radius * packed_spacing(stones);
+    let mut points: Vec<(i32, f32, Point)> = Vec::new();
+    for q in -2i32..=2 {
+        for r in -2i32..=2 {
+            let ring = q.abs().max(r.abs()).max((q + r).abs());
+            if ring > 2 {
+                continue;
+            }
+            let x = q as f32 + r as f32 / 2.0;
+            let y = r as f32 * 3.0f32.sqrt() / 2.0;
+            let angle = y.atan2(x).rem_euclid(2.0 * PI);
+            points.push((
+                ring,
+                angle,
+                Point::new(center.x + x * spacing, center.y + y * spacing),
+            ));
+        }
+    }
+    points.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    points
+        .into_iter()
+        .take(stones as usize)
+        .map(|(_, _, point)| point)
+        .collect()
+}
+
+// Where each stone of a stack sits on its hex. A badge is a single stone.
+pub fn stone_centers(stones: u8, center: Point, hexagon_radius: f32) -> Vec<Point> {
+    let mut centers = Vec::new();
+
+    if is_badge(stones, hexagon_radius) {
+        return vec![center];
+    }
     match stones {
-        1 => paths.push(draw_circles(center.x, center.y, radius)),
+        1 => centers.push(Point::new(center.x, center.y)),
         2 => {
-            paths.push(draw_circles(
-                center.x - hexagon_radius / 4.0,
-                center.y,
-                radius,
-            ));
-            paths.push(draw_circles(
-                center.x + hexagon_radius / 4.0,
-                center.y,
-                radius,
-            ));
+            centers.push(Point::new(center.x - hexagon_radius / 4.0, center.y));
+            centers.push(Point::new(center.x + hexagon_radius / 4.0, center.y));
         }
         3 => {
-            paths.push(draw_circles(
-                center.x,
-                center.y - hexagon_radius / 4.0,
-                radius,
-            ));
-            paths.push(draw_circles(
+            centers.push(Point::new(center.x, center.y - hexagon_radius / 4.0));
+            centers.push(Point::new(
                 center.x + (hexagon_radius / 4.0) * (3.0f32.sqrt() / 2.0),
                 center.y + hexagon_radius / 8.0,
-                radius,
             ));
-            paths.push(draw_circles(
+            centers.push(Point::new(
                 center.x - (hexagon_radius / 4.0) * (3.0f32.sqrt() / 2.0),
                 center.y + hexagon_radius / 8.0,
-                radius,
             ));
         }
         4 => {
-            paths.push(draw_circles(
+            centers.push(Point::new(
                 center.x - hexagon_radius / 4.0,
                 center.y - hexagon_radius / 4.0,
-                radius,
             ));
-            paths.push(draw_circles(
+            centers.push(Point::new(
                 center.x + hexagon_radius / 4.0,
                 center.y - hexagon_radius / 4.0,
-                radius,
             ));
-            paths.push(draw_circles(
+            centers.push(Point::new(
                 center.x - hexagon_radius / 4.0,
                 center.y + hexagon_radius / 4.0,
-                radius,
             ));
-            paths.push(draw_circles(
+            centers.push(Point::new(
                 center.x + hexagon_radius / 4.0,
                 center.y + hexagon_radius / 4.0,
-                radius,
             ));
         }
         5 => {
-            paths.push(draw_circles(
+            centers.push(Point::new(
                 center.x - hexagon_radius / 4.0,
                 center.y - hexagon_radius / 4.0,
-                radius,
             ));
-            paths.push(draw_circles(
+            centers.push(Point::new(
                 center.x + hexagon_radius / 4.0,
                 center.y - hexagon_radius / 4.0,
-                radius,
             ));
-            paths.push(draw_circles(
+            centers.push(Point::new(
                 center.x - hexagon_radius / 4.0,
                 center.y + hexagon_radius / 4.0,
-                radius,
             ));
-            paths.push(draw_circles(
+            centers.push(Point::new(
                 center.x + hexagon_radius / 4.0,
                 center.y + hexagon_radius / 4.0,
-                radius,
             ));
-            paths.push(draw_circles(center.x, center.y, radius));
+            centers.push(Point::new(center.x, center.y));
         }
         6 => {
-            paths.push(draw_circles(
-                center.x + hexagon_radius / 3.0,
-                center.y,
-                radius,
-            ));
-            paths.push(draw_circles(
+            centers.push(Point::new(center.x + hexagon_radius / 3.0, center.y));
+            centers.push(Point::new(
                 center.x + (hexagon_radius / 3.0) * (PI / 3.0).cos(),
                 center.y + (hexagon_radius / 3.0) * (PI / 3.0).sin(),
-                radius,
             ));
-            paths.push(draw_circles(
+            centers.push(Point::new(
                 center.x + (hexagon_radius / 3.0) * (2.0 * PI / 3.0).cos(),
                 center.y + (hexagon_radius / 3.0) * (2.0 * PI / 3.0).sin(),
-                radius,
             ));
-            paths.push(draw_circles(
+            centers.push(Point::new(
                 center.x + (hexagon_radius / 3.0) * (3.0 * PI / 3.0).cos(),
                 center.y + (hexagon_radius / 3.0) * (3.0 * PI / 3.0).sin(),
-                radius,
             ));
-            paths.push(draw_circles(
+            centers.push(Point::new(
                 center.x + (hexagon_radius / 3.0) * (4.0 * PI / 3.0).cos(),
                 center.y + (hexagon_radius / 3.0) * (4.0 * PI / 3.0).sin(),
-                radius,
             ));
-            paths.push(draw_circles(
+            centers.push(Point::new(
                 center.x + (hexagon_radius / 3.0) * (5.0 * PI / 3.0).cos(),
                 center.y + (hexagon_radius / 3.0) * (5.0 * PI / 3.0).sin(),
-                radius,
             ));
         }
-
-        _ => paths.push(draw_circles(center.x, center.y, radius)),
+        _ => centers = packed_centers(stones, center, hexagon_radius),
     }
-    paths
+    centers
 }
 
-fn draw_circles(center_x: f32, center_y: f32, radius: f32) -> Path {
-    Path::circle(Point::new(center_x, center_y), radius)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RADIUS: f32 = 100.0;
+
+    fn distance(a: Point, b: Point) -> f32 {
+        ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn stacks_up_to_the_limit_fit_without_overlapping() {
+        let center = Point::new(200.0, 200.0);
+        // Distance from the centre to the middle of a hexagon side
+        let inner = RADIUS * 3.0f32.sqrt() / 2.0;
+        for stones in 1..=MAX_PACKED {
+            assert!(!is_badge(stones, RADIUS), "{} stones", stones);
+            let radius = stone_radius(stones, RADIUS);
+            let centers = stone_centers(stones, center, RADIUS);
+            assert_eq!(centers.len(), stones as usize);
+            for (i, a) in centers.iter().enumerate() {
+                assert!(
+                    distance(*a, center) + radius <= inner,
+                    "{} stones: stone {} leaves the hex",
+                    stones,
+                    i
+                );
+                for b in &centers[i + 1..] {
+                    assert!(
+                        distance(*a, *b) >= 2.0 * radius - 1e-3,
+                        "{} stones: stones overlap",
+                        stones
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn big_stacks_and_small_hexes_use_a_badge() {
+        let center = Point::new(0.0, 0.0);
+        for stones in [MAX_PACKED + 1, 40, u8::MAX] {
+            assert!(is_badge(stones, RADIUS));
+            assert_eq!(stone_centers(stones, center, RADIUS), [center]);
+            let parts = piece_parts(
+                stones,
+                false,
+                Color::WHITE,
+                Color::BLACK,
+                None,
+                center,
+                RADIUS,
+            );
+            assert!(parts
+                .iter()
+                .any(|part| part.shape == Shape::Count(stones, center, RADIUS / 4.0)));
+        }
+        // Too small to tell seven stones apart, but six still fit
+        assert!(is_badge(7, 20.0));
+        assert!(!is_badge(6, 20.0));
+    }
 }
@@ -10,15 +10,12 @@ pub struct Player {
 }
 
 impl Player {
-    pub fn new(data: (PlayerID, u8, bool)) -> Self {
+    pub fn new(data: (PlayerID, u8, bool), color: Color) -> Self {
         Player {
             id: data.0,
             stones: data.1,
             king: data.2,
-            color: match data.0 {
-                PlayerID::One => Color::from_rgb8(0xFF, 0xFF, 0xFF),
-                PlayerID::Two => Color::from_rgb8(0x00, 0x00, 0x00),
-            },
+            color,
         }
     }
 
@@ -29,7 +26,7 @@ impl Player {
     }
 
     pub fn id(&self) -> PlayerID {
-        self.id.clone()
+        self.id
     }
 
     pub fn stones(&self) -> u8 {
@@ -43,4 +40,8 @@ impl Player {
     pub fn color(&self) -> Color {
         self.color
     }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
 }
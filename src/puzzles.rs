@@ -0,0 +1,159 @@
+// Puzzle mode over the built-in puzzles from `sixes_game::puzzle`. Every move
+// is checked against the solver; the engine answers for the opponent, which
+// also shows why a wrong move fails. Solved puzzles are remembered between
+// sessions as their puzzle lines, one per line.
+use std::sync::OnceLock;
+
+use sixes_game::agent::Agent;
+use sixes_game::engine::DEFAULT_DEPTH;
+use sixes_game::puzzle::{built_in, solutions, Goal, Puzzle};
+use sixes_game::{Coord, Play, PlayerID, Sixes};
+
+use crate::locale::{tr, tr_args};
+use crate::storage;
+
+const SOLVED_SLOT: &str = "puzzles";
+
+pub fn all() -> &'static [Puzzle] {
+    static PUZZLES: OnceLock<Vec<Puzzle>> = OnceLock::new();
+    PUZZLES.get_or_init(built_in)
+}
+
+pub fn solved() -> Vec<String> {
+    storage::read(SOLVED_SLOT)
+        .map(|text| text.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn mark_solved(puzzle: &Puzzle) -> Result<(), String> {
+    let mut solved = solved();
+    let line = puzzle.to_line();
+    if solved.contains(&line) {
+        return Ok(());
+    }
+    solved.push(line);
+    storage::write(SOLVED_SLOT, &(solved.join("\n") + "\n"))
+}
+
+// The goal in the player's language; `Goal`'s own Display is English
+pub fn goal_text(goal: Goal) -> String {
+    match goal {
+        Goal::WinIn(turns) => tr_args("goal-win", &[("turns", turns.into())]),
+        Goal::Survive(turns) => tr_args("goal-survive", &[("turns", turns.into())]),
+        Goal::PreventTriple => tr("goal-prevent"),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Playing,
+    Solved,
+    Failed,
+}
+
+// One go at a puzzle: the goal left and how it is going
+#[derive(Debug, Clone)]
+pub struct Attempt {
+    index: usize,
+    player: PlayerID,
+    goal: Goal,
+    status: Status,
+    feedback: String,
+}
+
+impl Attempt {
+    pub fn new(index: usize) -> Self {
+        let index = index.min(all().len().saturating_sub(1));
+        let puzzle = &all()[index];
+        let player = puzzle
+            .position()
+            .map(|game| game.turn())
+            .unwrap_or(PlayerID::One);
+        Attempt {
+            index,
+            player,
+            goal: puzzle.goal,
+            status: Status::Playing,
+            feedback: String::new(),
+        }
+    }
+
+    pub fn puzzle(&self) -> &'static Puzzle {
+        &all()[self.index]
+    }
+
+    pub fn number(&self) -> usize {
+        self.index
+    }
+
+    pub fn player(&self) -> PlayerID {
+        self.player
+    }
+
+    pub fn goal(&self) -> Goal {
+        self.goal
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn feedback(&self) -> &str {
+        &self.feedback
+    }
+
+    pub fn position(&self) -> Sixes {
+        self.puzzle().position().unwrap_or_else(|_| Sixes::start())
+    }
+
+    // Whether the player may make this move. Wrong moves are still played so
+    // the engine's answer can show what goes wrong, but the attempt has failed.
+    pub fn attempt(&mut self, game: &Sixes, coord: Coord, play: Play) -> bool {
+        if self.status != Status::Playing || game.turn() != self.player {
+            return false;
+        }
+        if game.dry_run(coord, play).is_err() {
+            return false;
+        }
+        if !solutions(game, self.goal).contains(&(coord, play)) {
+            self.status = Status::Failed;
+            self.feedback = tr("puzzle-wrong");
+        }
+        true
+    }
+
+    // The opponent's answer to the player's last move, if one is due
+    pub fn reply(&self, game: &Sixes) -> Option<(Coord, Play)> {
+        if game.turn() == self.player || game.game_state().winner().is_some() {
+            return None;
+        }
+        Agent::from_config(DEFAULT_DEPTH, game.history().len() as u64).choose_move(game)
+    }
+
+    // Takes stock after the opponent has answered
+    pub fn after_reply(&mut self, game: &Sixes) {
+        if self.status != Status::Playing {
+            return;
+        }
+        match (game.game_state().winner(), self.goal.next()) {
+            (Some(winner), _) if winner != self.player => self.fail(),
+            (None, Some(next)) => {
+                self.goal = next;
+                self.feedback = tr_args("puzzle-good", &[("goal", goal_text(next).into())]);
+            }
+            (None, None) if matches!(self.goal, Goal::WinIn(_)) => self.fail(),
+            _ => {
+                self.status = Status::Solved;
+                self.feedback = match mark_solved(self.puzzle()) {
+                    Ok(()) => tr("puzzle-solved"),
+                    Err(message) => tr_args("puzzle-solved-unsaved", &[("error", message.into())]),
+                };
+            }
+        }
+    }
+
+    fn fail(&mut self) {
+        self.status = Status::Failed;
+        self.feedback = tr("puzzle-failed");
+    }
+}
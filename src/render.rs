@@ -0,0 +1,372 @@
+// Draws positions without a window, for sharing in chat and docs: to SVG or
+// rasterised to PNG. The geometry comes from `HexagonGrid` and `pieces`, so
+// images match the board on screen. Desktop only, like the command line.
+//
+//   sixes render <notation> -o pos.svg [--size 600] [--theme dark] [--coordinates]
+//
+// The output format follows the file extension, `.png` or anything else for SVG.
+use std::fmt::Write;
+
+use iced::{Color, Point, Size};
+use resvg::{tiny_skia, usvg};
+use sixes_game::notation::parse_moves;
+use sixes_game::{Coord, PlayerID, Sixes, CENTER_HEX};
+
+use crate::hexagon::HexagonGrid;
+use crate::palette::{Palette, ThemeSetting};
+use crate::pieces::{piece_parts, Part, Shape};
+
+const DEFAULT_SIZE: f32 = 600.0;
+const USAGE: &str =
+    "usage: sixes render <notation> -o <file.svg|file.png> [--size 600] [--theme classic|dark|high-contrast|colour-blind] [--coordinates]";
+
+// What to draw and how, besides the position itself
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub size: Size,
+    pub palette: Palette,
+    pub coordinates: bool,
+    // Lines of text under the board, the first one larger
+    pub caption: Vec<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            size: Size::new(DEFAULT_SIZE, DEFAULT_SIZE),
+            palette: Palette::default(),
+            coordinates: false,
+            caption: Vec::new(),
+        }
+    }
+}
+
+fn hex_color(color: Color) -> String {
+    let [r, g, b, _] = color.into_rgba8();
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+// `fill` or `stroke` attributes for a colour, with its opacity when see-through
+fn paint(attribute: &str, color: Color) -> String {
+    if color.a < 1.0 {
+        format!(
+            "{}=\"{}\" {}-opacity=\"{:.2}\"",
+            attribute,
+            hex_color(color),
+            attribute,
+            color.a
+        )
+    } else {
+        format!("{}=\"{}\"", attribute, hex_color(color))
+    }
+}
+
+fn points(points: &[Point]) -> String {
+    points
+        .iter()
+        .map(|point| format!("{:.1},{:.1}", point.x, point.y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// `content` with the characters that mean something in XML escaped
+fn escape(content: &str) -> String {
+    let mut escaped = String::with_capacity(content.len());
+    for c in content.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn text(svg: &mut String, content: &str, position: Point, size: f32, color: Color) {
+    let _ = writeln!(
+        svg,
+        "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"sans-serif\" font-size=\"{:.1}\" text-anchor=\"middle\" dominant-baseline=\"central\" {}>{}</text>",
+        position.x,
+        position.y,
+        size,
+        paint("fill", color),
+        escape(content)
+    );
+}
+
+fn draw_part(svg: &mut String, part: Part) {
+    let fill = match part.fill {
+        Some(color) => paint("fill", color),
+        None => String::from("fill=\"none\""),
+    };
+    let outline = match part.outline {
+        Some(color) => format!(" {} stroke-width=\"2\"", paint("stroke", color)),
+        None => String::new(),
+    };
+    match part.shape {
+        Shape::Circle(center, radius) => {
+            let _ = writeln!(
+                svg,
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" {}{}/>",
+                center.x, center.y, radius, fill, outline
+            );
+        }
+        Shape::Crown(corners) => {
+            let _ = writeln!(
+                svg,
+                "<polygon points=\"{}\" {}{}/>",
+                points(&corners),
+                fill,
+                outline
+            );
+        }
+        Shape::Count(stones, center, size) => text(
+            svg,
+            &stones.to_string(),
+            center,
+            size,
+            part.fill.unwrap_or(Color::BLACK),
+        ),
+    }
+}
+
+// Draws the hexes, stacks and kings of `game`, its triples and a dashed ring
+// around the hex of the last move, with any caption underneath
+pub fn svg(game: &Sixes, options: &Options) -> String {
+    let (palette, coordinates) = (options.palette, options.coordinates);
+    // The caption takes a band off the bottom and the board fits the rest
+    let line = (options.size.width / 40.0).max(12.0);
+    let band = if options.caption.is_empty() {
+        0.0
+    } else {
+        line * (1.6 * options.caption.len() as f32 + 0.8)
+    };
+    let size = Size::new(options.size.width, options.size.height - band);
+    let grid = HexagonGrid::new(5.0, 5.0);
+    let radius = grid.radius(size);
+    let background = if palette.dark {
+        Color::from_rgb8(0x20, 0x22, 0x25)
+    } else {
+        Color::WHITE
+    };
+    let color = |player: PlayerID| match player {
+        PlayerID::One => palette.player_one,
+        PlayerID::Two => palette.player_two,
+    };
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = options.size.width,
+        h = options.size.height
+    );
+    let _ = writeln!(
+        svg,
+        "<rect width=\"100%\" height=\"100%\" {}/>",
+        paint("fill", background)
+    );
+
+    for coord in Coord::ALL {
+        let fill = if coord == CENTER_HEX {
+            paint("fill", palette.centre)
+        } else {
+            String::from("fill=\"none\"")
+        };
+        let _ = writeln!(
+            svg,
+            "<polygon points=\"{}\" {} {} stroke-width=\"2\"/>",
+            points(&grid.corners(size, coord)),
+            fill,
+            paint("stroke", palette.board_line)
+        );
+        if coordinates {
+            let center = grid.center(size, coord);
+            text(
+                &mut svg,
+                &coord.to_string(),
+                Point::new(center.x, center.y - radius * 0.7),
+                (radius / 5.0).max(10.0),
+                palette.board_line,
+            );
+        }
+    }
+
+    for (coord, stones, king, owner) in game.board() {
+        let Some(owner) = owner else {
+            continue;
+        };
+        // Player Two's pieces get an inner ring in Player One's colour
+        let mark = (palette.marked && owner == PlayerID::Two).then_some(palette.player_one);
+        let center = grid.center(size, coord);
+        let parts = piece_parts(
+            stones,
+            king,
+            color(owner),
+            color(owner.other()),
+            mark,
+            center,
+            radius,
+        );
+        for part in parts {
+            draw_part(&mut svg, part);
+        }
+    }
+
+    // Thick lines for triples the side to move has to break, thin for its own
+    for triple in game.triples() {
+        let coords = triple.coords();
+        let (from, to) = (grid.center(size, coords[0]), grid.center(size, coords[2]));
+        let (width, stroke) = if triple.player() != game.turn() {
+            (8.0, palette.winning_triple)
+        } else {
+            (5.0, palette.own_triple)
+        };
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" {} stroke-width=\"{}\" stroke-linecap=\"round\"/>",
+            from.x,
+            from.y,
+            to.x,
+            to.y,
+            paint("stroke", stroke),
+            width
+        );
+    }
+
+    if let Some((coord, _)) = game.history().last() {
+        let center = grid.center(size, *coord);
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"none\" {} stroke-width=\"4\" stroke-dasharray=\"6 6\"/>",
+            center.x,
+            center.y,
+            radius * 0.8,
+            paint("stroke", palette.focus)
+        );
+    }
+
+    for (i, content) in options.caption.iter().enumerate() {
+        let (y, font) = match i {
+            0 => (size.height + line, line * 1.2),
+            _ => (size.height + line * (1.0 + 1.6 * i as f32), line),
+        };
+        text(
+            &mut svg,
+            content,
+            Point::new(options.size.width / 2.0, y),
+            font,
+            palette.board_line,
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+pub fn png(svg: &str) -> Result<Vec<u8>, String> {
+    rasterise(svg)?.encode_png().map_err(|e| e.to_string())
+}
+
+// Draws an SVG from `svg` into pixels, using the system fonts for any text
+pub fn rasterise(svg: &str) -> Result<tiny_skia::Pixmap, String> {
+    let mut options = usvg::Options::default();
+    let fonts = options.fontdb_mut();
+    fonts.load_system_fonts();
+    // fontdb takes "sans-serif" to mean Arial; use whatever is installed instead
+    let query = usvg::fontdb::Query {
+        families: &[usvg::fontdb::Family::SansSerif],
+        ..usvg::fontdb::Query::default()
+    };
+    if fonts.query(&query).is_none() {
+        let installed = fonts
+            .faces()
+            .find_map(|face| face.families.first().map(|(family, _)| family.clone()));
+        if let Some(family) = installed {
+            fonts.set_sans_serif_family(family);
+        }
+    }
+    let tree = usvg::Tree::from_str(svg, &options).map_err(|e| e.to_string())?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| String::from("image has no area"))?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    Ok(pixmap)
+}
+
+// Splits the arguments shared by the image commands from the rest, which are
+// returned in order. `usage` is the error when the output file is missing.
+pub fn parse_args<'a>(
+    args: &'a [String],
+    usage: &str,
+) -> Result<(Vec<&'a str>, &'a str, Options), String> {
+    let mut rest = Vec::new();
+    let mut out = None;
+    let mut options = Options::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => out = args.next(),
+            "--size" => {
+                let value = args.next().ok_or(usage)?;
+                let side = value
+                    .parse()
+                    .ok()
+                    .filter(|side: &f32| side.is_finite() && *side > 0.0)
+                    .ok_or_else(|| format!("invalid size: {}", value))?;
+                options.size = Size::new(side, side);
+            }
+            "--theme" => {
+                let value = args.next().ok_or(usage)?;
+                let theme = ThemeSetting::from_key(value)
+                    .ok_or_else(|| format!("unknown theme: {}", value))?;
+                options.palette = theme.palette();
+            }
+            "--coordinates" => options.coordinates = true,
+            _ => rest.push(arg.as_str()),
+        }
+    }
+    let out = out.ok_or(usage)?;
+    Ok((rest, out, options))
+}
+
+// Runs `sixes render` with the arguments after "render"
+pub fn command(args: &[String]) -> Result<(), String> {
+    let (notation, out, options) = parse_args(args, USAGE)?;
+    let moves = parse_moves(&notation.join(" ")).map_err(|e| format!("invalid moves: {}", e.0))?;
+    let game = Sixes::from_moves(&moves).map_err(|e| format!("illegal moves: {:?}", e))?;
+    let svg = svg(&game, &options);
+    if out.to_lowercase().ends_with(".png") {
+        std::fs::write(out, png(&svg)?).map_err(|e| e.to_string())
+    } else {
+        std::fs::write(out, svg).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captions_are_escaped() {
+        let options = Options {
+            caption: vec![String::from("Black & White <3")],
+            ..Options::default()
+        };
+        let svg = svg(&Sixes::start(), &options);
+        assert!(svg.contains(">Black &amp; White &lt;3</text>"));
+    }
+
+    #[test]
+    fn sizes_must_be_positive_and_finite() {
+        let args = |size: &str| ["-o", "pos.svg", "--size", size].map(String::from).to_vec();
+        for size in ["0", "-5", "NaN", "inf", "big"] {
+            assert!(parse_args(&args(size), USAGE).is_err(), "{}", size);
+        }
+        let (_, _, options) = parse_args(&args("300"), USAGE).unwrap();
+        assert_eq!(options.size, Size::new(300.0, 300.0));
+    }
+}
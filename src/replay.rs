@@ -0,0 +1,147 @@
+// Replays a saved game as an animated GIF or a directory of numbered SVG
+// frames for write-ups. Each move gets a frame captioned with its number,
+// notation and description, and the last frame shows the result. Games are
+// read from the record format in `sixes_game::record`, so no window is needed.
+//
+//   sixes replay <record> -o game.gif [--delay 1000] [--size 600] [--theme dark] [--coordinates]
+//   sixes replay <record> -o frames
+use std::fs::{self, File};
+use std::path::Path;
+
+use sixes_game::notation::move_to_string;
+use sixes_game::record::from_record;
+use sixes_game::{GameState, Sixes};
+
+use crate::describe::describe_last_move;
+use crate::locale::{player as name, tr, tr_args};
+use crate::render::{parse_args, rasterise, svg, Options};
+
+const DEFAULT_DELAY_MS: u16 = 1000;
+// The result stays up this many times longer than a move
+const RESULT_HOLD: u16 = 3;
+const USAGE: &str =
+    "usage: sixes replay <record> -o <game.gif|directory> [--delay 1000] [--size 600] [--theme classic|dark|high-contrast|colour-blind] [--coordinates]";
+
+fn result(game: &Sixes) -> String {
+    match game.game_state() {
+        GameState::Win(player) => tr_args("replay-win", &[("player", name(player).into())]),
+        GameState::WinOnTime(player) => {
+            tr_args("replay-win-on-time", &[("player", name(player).into())])
+        }
+        GameState::Running => tr("replay-unfinished"),
+    }
+}
+
+// The empty board, the position after each move and the final position again
+// with the result. Every caption has two lines so the board stays put.
+pub fn frames(game: &Sixes, options: &Options) -> Vec<String> {
+    let captioned = |position: &Sixes, caption: [String; 2]| {
+        svg(
+            position,
+            &Options {
+                caption: caption.to_vec(),
+                ..options.clone()
+            },
+        )
+    };
+    let mut replay = Sixes::start();
+    let mut frames = vec![captioned(&replay, [tr("start"), String::new()])];
+    for (i, (coord, play)) in game.history().into_iter().enumerate() {
+        if replay.play(coord, play).is_err() {
+            break;
+        }
+        let caption = [
+            format!("{}. {}", i + 1, move_to_string(coord, play)),
+            describe_last_move(&replay),
+        ];
+        frames.push(captioned(&replay, caption));
+    }
+    frames.push(captioned(game, [result(game), describe_last_move(game)]));
+    frames
+}
+
+fn write_gif(frames: &[String], path: &str, delay_ms: u16) -> Result<(), String> {
+    let mut encoder = None;
+    for (i, frame) in frames.iter().enumerate() {
+        let mut pixmap = rasterise(frame)?;
+        // GIF dimensions are 16 bit, caption band included
+        let too_large = |_| {
+            format!(
+                "frames of {}x{} are too large for a GIF",
+                pixmap.width(),
+                pixmap.height()
+            )
+        };
+        let width = u16::try_from(pixmap.width()).map_err(too_large)?;
+        let height = u16::try_from(pixmap.height()).map_err(too_large)?;
+        let encoder = match &mut encoder {
+            Some(encoder) => encoder,
+            None => {
+                let file = File::create(path).map_err(|e| e.to_string())?;
+                let mut created =
+                    gif::Encoder::new(file, width, height, &[]).map_err(|e| e.to_string())?;
+                created
+                    .set_repeat(gif::Repeat::Infinite)
+                    .map_err(|e| e.to_string())?;
+                encoder.insert(created)
+            }
+        };
+        // The background is opaque, so premultiplied pixels are plain RGBA
+        let mut gif_frame = gif::Frame::from_rgba_speed(width, height, pixmap.data_mut(), 10);
+        let hold = if i + 1 == frames.len() {
+            RESULT_HOLD
+        } else {
+            1
+        };
+        gif_frame.delay = delay_ms / 10 * hold;
+        encoder.write_frame(&gif_frame).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn write_svgs(frames: &[String], directory: &str) -> Result<(), String> {
+    fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+    for (i, frame) in frames.iter().enumerate() {
+        let path = Path::new(directory).join(format!("frame-{:03}.svg", i));
+        fs::write(path, frame).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// Runs `sixes replay` with the arguments after "replay"
+pub fn command(args: &[String]) -> Result<(), String> {
+    let (rest, out, options) = parse_args(args, USAGE)?;
+    let mut delay_ms = DEFAULT_DELAY_MS;
+    let mut record = None;
+    let mut rest = rest.into_iter();
+    while let Some(arg) = rest.next() {
+        match arg {
+            "--delay" => {
+                let value = rest.next().ok_or(USAGE)?;
+                delay_ms = value
+                    .parse()
+                    .map_err(|_| format!("invalid delay: {}", value))?;
+            }
+            path => record = Some(path),
+        }
+    }
+    let record = record.ok_or(USAGE)?;
+
+    let text = fs::read_to_string(record).map_err(|e| format!("{}: {}", record, e))?;
+    let game = from_record(&text).map_err(|e| format!("invalid record: {:?}", e))?;
+    let gif = out.to_lowercase().ends_with(".gif");
+    // Checked up front so an oversized GIF fails before any frame is drawn
+    if gif && options.size.width > u16::MAX as f32 {
+        return Err(format!(
+            "invalid size: {} is larger than a GIF allows ({})",
+            options.size.width,
+            u16::MAX
+        ));
+    }
+    let frames = frames(&game, &options);
+    if gif {
+        write_gif(&frames, out, delay_ms)
+    } else {
+        write_svgs(&frames, out)
+    }
+}
@@ -0,0 +1,82 @@
+use crate::locale::Language;
+use crate::palette::ThemeSetting;
+use crate::storage;
+
+const SETTINGS_SLOT: &str = "settings";
+
+// Preferences kept between sessions, stored as `name = value` lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub animations: bool,
+    pub theme: ThemeSetting,
+    // Hex names drawn on the board
+    pub coordinates: bool,
+    pub language: Language,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            animations: true,
+            theme: ThemeSetting::Classic,
+            coordinates: false,
+            language: Language::default(),
+        }
+    }
+}
+
+impl Settings {
+    // Unknown names and bad values are skipped so old files keep loading
+    fn parse(text: &str) -> Self {
+        let mut settings = Settings::default();
+        for line in text.lines() {
+            let Some((name, value)) = line.split_once('=') else {
+                continue;
+            };
+            match name.trim() {
+                "animations" => {
+                    if let Ok(animations) = value.trim().parse() {
+                        settings.animations = animations;
+                    }
+                }
+                "theme" => {
+                    if let Some(theme) = ThemeSetting::from_key(value.trim()) {
+                        settings.theme = theme;
+                    }
+                }
+                "coordinates" => {
+                    if let Ok(coordinates) = value.trim().parse() {
+                        settings.coordinates = coordinates;
+                    }
+                }
+                "language" => {
+                    if let Some(language) = Language::from_key(value.trim()) {
+                        settings.language = language;
+                    }
+                }
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    fn to_text(&self) -> String {
+        format!(
+            "animations = {}\ntheme = {}\ncoordinates = {}\nlanguage = {}\n",
+            self.animations,
+            self.theme.key(),
+            self.coordinates,
+            self.language.key()
+        )
+    }
+
+    pub fn load() -> Self {
+        storage::read(SETTINGS_SLOT)
+            .map(|text| Settings::parse(&text))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        storage::write(SETTINGS_SLOT, &self.to_text())
+    }
+}
@@ -0,0 +1,52 @@
+// Small text key/value store for saved games and settings: one file per key in
+// the user's data directory on desktop, localStorage on the wasm build.
+pub const SAVE_SLOT: &str = "save";
+pub const AUTOSAVE_SLOT: &str = "autosave";
+
+#[cfg(not(target_arch = "wasm32"))]
+fn path(key: &str) -> std::path::PathBuf {
+    use std::env;
+    use std::path::PathBuf;
+
+    let dir = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .unwrap_or_else(|| PathBuf::from("."));
+    dir.join("sixes").join(format!("{}.txt", key))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read(key: &str) -> Option<String> {
+    std::fs::read_to_string(path(key)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write(key: &str, contents: &str) -> Result<(), String> {
+    let path = path(key);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, contents).map_err(|e| e.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+use crate::locale::tr;
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read(key: &str) -> Option<String> {
+    local_storage()?.get_item(&format!("sixes.{}", key)).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write(key: &str, contents: &str) -> Result<(), String> {
+    local_storage()
+        .ok_or_else(|| tr("storage-unavailable"))?
+        .set_item(&format!("sixes.{}", key), contents)
+        .map_err(|_| tr("storage-write-failed"))
+}
@@ -0,0 +1,237 @@
+// Step-by-step lessons on scripted positions. Each lesson sets up a position
+// with the move list in `setup`, explains a rule and only accepts the moves
+// in `targets`; any other move gets feedback on why it was turned down.
+use sixes_game::notation::{parse_move, parse_moves};
+use sixes_game::{Coord, Play, Sixes, CENTER_HEX};
+
+use crate::locale::{tr, tr_args};
+
+#[derive(Debug)]
+pub struct Lesson {
+    // Prefix of the lesson's messages in the catalogues: title, explanation,
+    // feedback for a legal move that misses the point and success
+    key: &'static str,
+    setup: &'static str,
+    targets: &'static [&'static str],
+    // Played for the opponent once the lesson is solved, to show what follows
+    reply: Option<&'static str>,
+}
+
+pub const LESSONS: [Lesson; 5] = [
+    Lesson {
+        key: "empty",
+        setup: "",
+        targets: &["A1", "B1", "C1", "D1", "B2", "D2", "B3", "C3", "D3", "E3"],
+        reply: None,
+    },
+    Lesson {
+        key: "capture",
+        setup: "B1 C1 D1 B1 A1 D3",
+        targets: &["B1"],
+        reply: None,
+    },
+    Lesson {
+        key: "king",
+        setup: "B1 C1 D1 B1 A1 D3",
+        targets: &["KB1"],
+        reply: None,
+    },
+    Lesson {
+        key: "last-played",
+        setup: "B1 C1 D1 B1",
+        targets: &["C1"],
+        reply: None,
+    },
+    Lesson {
+        key: "triple",
+        setup: "A1 D3 B1 E3",
+        targets: &["C1"],
+        reply: Some("D2"),
+    },
+];
+
+impl Lesson {
+    fn message(&self, part: &str) -> String {
+        tr(&format!("lesson-{}-{}", self.key, part))
+    }
+
+    pub fn title(&self) -> String {
+        self.message("title")
+    }
+
+    pub fn explanation(&self) -> String {
+        self.message("explanation")
+    }
+
+    pub fn position(&self) -> Sixes {
+        let moves = parse_moves(self.setup).expect("lesson setups are valid notation");
+        Sixes::from_moves(&moves).expect("lesson setups are legal")
+    }
+
+    fn is_target(&self, coord: Coord, play: Play) -> bool {
+        self.targets
+            .iter()
+            .any(|target| parse_move(target).is_ok_and(|target| target == (coord, play)))
+    }
+
+    fn reply(&self) -> Option<(Coord, Play)> {
+        self.reply.and_then(|reply| parse_move(reply).ok())
+    }
+}
+
+// Progress through the lessons
+#[derive(Debug, Clone)]
+pub struct Tutorial {
+    lesson: usize,
+    solved: bool,
+    replied: bool,
+    feedback: String,
+}
+
+impl Tutorial {
+    pub fn new(lesson: usize) -> Self {
+        Tutorial {
+            lesson: lesson.min(LESSONS.len() - 1),
+            solved: false,
+            replied: false,
+            feedback: String::new(),
+        }
+    }
+
+    pub fn lesson(&self) -> &'static Lesson {
+        &LESSONS[self.lesson]
+    }
+
+    pub fn number(&self) -> usize {
+        self.lesson
+    }
+
+    pub fn solved(&self) -> bool {
+        self.solved
+    }
+
+    pub fn feedback(&self) -> &str {
+        &self.feedback
+    }
+
+    // Whether `play` on `coord` may be made, setting the feedback either way
+    pub fn attempt(&mut self, game: &Sixes, coord: Coord, play: Play) -> bool {
+        if self.solved {
+            self.feedback = tr("lesson-complete");
+            return false;
+        }
+        if let Some(reason) = why_illegal(game, coord, play) {
+            self.feedback = reason;
+            return false;
+        }
+        if !self.lesson().is_target(coord, play) {
+            self.feedback = self.lesson().message("wrong");
+            return false;
+        }
+        self.solved = true;
+        self.feedback = self.lesson().message("success");
+        true
+    }
+
+    // The opponent's scripted answer, handed out once after the lesson is solved
+    pub fn take_reply(&mut self) -> Option<(Coord, Play)> {
+        if !self.solved || self.replied {
+            return None;
+        }
+        self.replied = true;
+        self.lesson().reply()
+    }
+}
+
+// The rule a move breaks, in words, or None for a legal move
+pub fn why_illegal(game: &Sixes, coord: Coord, play: Play) -> Option<String> {
+    if game.dry_run(coord, play).is_ok() {
+        return None;
+    }
+    if game.last_captured() == Some(coord) {
+        return Some(tr_args(
+            "illegal-last-played",
+            &[("hex", coord.to_string().into())],
+        ));
+    }
+    let turn = game.turn();
+    let board = game.board();
+    let (_, stones, king, owner) = board
+        .iter()
+        .find(|(hex, _, _, _)| *hex == coord)
+        .copied()
+        .unwrap_or((coord, 0, false, None));
+    let (_, supply, has_king) = game
+        .players()
+        .into_iter()
+        .find(|(player, _, _)| *player == turn)
+        .unwrap_or((turn, 0, false));
+    let reason = match play {
+        _ if king => tr("illegal-king-captured"),
+        _ if owner == Some(turn) => {
+            tr_args("illegal-own-hex", &[("hex", coord.to_string().into())])
+        }
+        Play::Stones if supply <= stones => tr_args(
+            "illegal-supply",
+            &[
+                ("stones", stones.into()),
+                ("needed", (stones + 1).into()),
+                ("supply", supply.into()),
+            ],
+        ),
+        Play::King if !has_king => tr("illegal-king-played"),
+        Play::King if coord == CENTER_HEX => tr("illegal-king-centre"),
+        Play::King if owner.is_none() => tr("illegal-king-empty"),
+        _ => tr("illegal-other"),
+    };
+    Some(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sixes_game::PlayerID;
+
+    #[test]
+    fn every_lesson_can_be_solved() {
+        for (i, lesson) in LESSONS.iter().enumerate() {
+            let mut game = lesson.position();
+            for target in lesson.targets {
+                let (coord, play) = parse_move(target).unwrap();
+                assert!(
+                    game.dry_run(coord, play).is_ok(),
+                    "{}: {}",
+                    lesson.title(),
+                    target
+                );
+            }
+            let mut tutorial = Tutorial::new(i);
+            let (coord, play) = parse_move(lesson.targets[0]).unwrap();
+            assert!(tutorial.attempt(&game, coord, play));
+            game.play(coord, play).unwrap();
+            if let Some((coord, play)) = tutorial.take_reply() {
+                game.play(coord, play).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn the_triple_lesson_ends_in_a_win() {
+        let mut tutorial = Tutorial::new(4);
+        let mut game = tutorial.lesson().position();
+        assert!(tutorial.attempt(&game, Coord::C1, Play::Stones));
+        game.play(Coord::C1, Play::Stones).unwrap();
+        let (coord, play) = tutorial.take_reply().unwrap();
+        game.play(coord, play).unwrap();
+        assert_eq!(game.game_state().winner(), Some(PlayerID::One));
+    }
+
+    #[test]
+    fn the_last_played_hex_is_explained() {
+        let mut tutorial = Tutorial::new(3);
+        let game = tutorial.lesson().position();
+        assert!(!tutorial.attempt(&game, Coord::B1, Play::Stones));
+        assert!(tutorial.feedback().contains("last turn"));
+        assert!(!tutorial.solved());
+    }
+}